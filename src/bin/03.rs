@@ -1,3 +1,5 @@
+use advent_of_code::grid::Grid;
+
 advent_of_code::solution!(3);
 
 #[derive(Debug)]
@@ -9,7 +11,7 @@ enum Cell {
 
 #[derive(Debug)]
 struct Map {
-    cells: Vec<Cell>,
+    cells: Grid<Cell>,
     width: i32,
     height: i32,
 }
@@ -55,20 +57,17 @@ impl Neighbouring for MapWindow {
 
 trait Mapable {
     fn get(&self, x: i32, y: i32) -> Option<&Cell>;
-    fn numbers(& self) -> MapNumbers;
+    fn numbers(& self) -> MapNumbers<'_>;
     fn neighbours(& self, window: &MapWindow) -> Vec<(i32, i32)>;
-    fn symbols(& self) -> MapSymbols;
+    fn symbols(& self) -> MapSymbols<'_>;
 }
 
 impl Mapable for Map {
     fn get(&self, x: i32, y: i32) -> Option<&Cell> {
-        if x >= self.width || y >= self.height || x < 0 || y < 0 {
-            return None;
-        }
-        self.cells.get((y * self.width + x) as usize)
+        self.cells.get_signed((y as isize, x as isize))
     }
 
-    fn numbers(& self) -> MapNumbers {
+    fn numbers(& self) -> MapNumbers<'_> {
         MapNumbers {
             map: self,
             curr_x: 0,
@@ -76,7 +75,7 @@ impl Mapable for Map {
         }
     }
 
-    fn symbols(& self) -> MapSymbols {
+    fn symbols(& self) -> MapSymbols<'_> {
         MapSymbols {
             map: self,
             curr_x: 0,
@@ -194,32 +193,18 @@ impl Iterator for MapSymbols<'_> {
 }
 
 fn parse(input: &str) -> Map {
-    let mut cells = Vec::new();
-    let mut width = 0i32;
-    let mut height = 0i32;
-
-    for line in input.lines() {
-        if width == 0 {
-            width = line.len() as i32;
-        }
-        else if width != line.len() as i32 {
-            panic!("Invalid input");
-        }
-        height += 1;
-        for c in line.chars() {
-            cells.push(match c {
-                '.' => Cell::Empty,
-                c => {
-                    if let Some(x) = c.to_digit(10) {
-                        Cell::Digit(x as u8)
-                    }
-                    else {
-                        Cell::Symbol(c)
-                    }
-                }
-            });
+    let cells = Grid::parse(input, |c| Some(match c {
+        '.' => Cell::Empty,
+        c => {
+            if let Some(x) = c.to_digit(10) {
+                Cell::Digit(x as u8)
+            }
+            else {
+                Cell::Symbol(c)
+            }
         }
-    }
+    })).unwrap();
+    let (height, width) = (cells.height() as i32, cells.width() as i32);
     Map { cells, width, height }
 }
 
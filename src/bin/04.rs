@@ -1,3 +1,4 @@
+use advent_of_code::parsing::*;
 advent_of_code::solution!(4);
 
 #[derive(Debug)]
@@ -9,17 +10,13 @@ struct Card {
 
 
 fn parse(input: &str) -> Vec<Card> {
-    input.lines().map(|line| {
-        let parts = line.split(':').collect::<Vec<_>>();
-        let id = parts[0].strip_prefix("Card").unwrap().trim().parse::<u32>().unwrap();
+    let card = tuple((
+        delimited(pair(tag("Card"), space1), unsigned, char(':')),
+        terminated(spaced_numbers, pair(space1, char('|'))),
+        spaced_numbers,
+    )).map(|(id, winning, numbers)| Card { _id: id, winning, numbers });
 
-        let number_parts = parts[1].split('|').collect::<Vec<_>>();
-
-        let winning = number_parts[0].split_ascii_whitespace().map(|x| x.parse::<u32>().unwrap() ).collect::<Vec<_>>();
-        let numbers = number_parts[1].split_ascii_whitespace().map(|x| x.parse::<u32>().unwrap() ).collect::<Vec<_>>();
-
-        Card { _id: id, winning, numbers }
-    }).collect()
+    parse_all(input, lines(card)).unwrap()
 }
 
 fn winnings(cards: &[Card]) -> Vec<u32> {
@@ -47,7 +44,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     let cards = parse(input);
     let winning = winnings(&cards);
     let n = cards.len();
-    let mut copies = std::iter::repeat(1).take(n).collect::<Vec<u32>>();
+    let mut copies = vec![1u32; n];
     for (idx, wins) in winning.into_iter().enumerate() {
 
         for i in idx+1..std::cmp::min(idx+(wins as usize)+1, n) {
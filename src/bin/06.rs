@@ -1,11 +1,13 @@
+use advent_of_code::parsing::*;
 advent_of_code::solution!(6);
 
 fn parse(input: &str) -> Vec<(u32, u32)> {
-    let lines = input.lines().collect::<Vec<_>>();
-    std::iter::zip(
-        lines[0].strip_prefix("Time:").unwrap().trim().split_ascii_whitespace().map(|x| x.parse::<u32>().unwrap()),
-        lines[1].strip_prefix("Distance:").unwrap().trim().split_ascii_whitespace().map(|x| x.parse::<u32>().unwrap())
-    ).collect()
+    let (times, distances) = parse_all(input, separated_pair(
+        section("Time", spaced_numbers),
+        line_ending,
+        section("Distance", spaced_numbers),
+    )).unwrap();
+    std::iter::zip(times, distances).collect()
 }
 
 fn find_solution(t: u64, d: u64) -> u64 {
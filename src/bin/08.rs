@@ -1,3 +1,6 @@
+use advent_of_code::crt::first_common_hit;
+use advent_of_code::cycle::find_hits;
+use advent_of_code::graph::{Graph, NodeId};
 advent_of_code::solution!(8);
 
 #[derive(Debug)]
@@ -6,16 +9,10 @@ enum Direction {
     Right,
 }
 
-#[derive(Debug)]
-struct Node {
-    name: String,
-    left: usize,
-    right: usize,
-}
-
-type Network = Vec<Node>;
+/// Every node has two successors, the left one first.
+type Network<'a> = Graph<&'a str>;
 
-fn parse(input: &str) -> (Vec<Direction>, Network) {
+fn parse(input: &str) -> (Vec<Direction>, Network<'_>) {
     let mut iter = input.lines();
     let dirs = iter.next().unwrap().trim().chars().map(|x| match x {
         'R' => Direction::Right,
@@ -24,34 +21,28 @@ fn parse(input: &str) -> (Vec<Direction>, Network) {
     }).collect::<Vec<_>>();
 
     let re = regex::Regex::new(r"([A-Z0-9]{3}) = \(([A-Z0-9]{3}), ([A-Z0-9]{3})\)").unwrap();
-    let net = iter.filter_map(|l| {
-        re.captures(l).map(|c| c.extract().1)
-    }).collect::<Vec<_>>();
-
-    let map = net.iter().enumerate().map(|(idx, [a, _, _])| (a.to_string(), idx))
-        .collect::<std::collections::HashMap<String, usize>>();
-
-    let net = net.into_iter().map(|[name, left, right]| {
-        Node {
-            name: name.to_string(),
-            left: *map.get(left).unwrap(),
-            right: *map.get(right).unwrap(),
-        }
-    }).collect::<Vec<_>>();
+    let mut net = Graph::new();
+    for [name, left, right] in iter.filter_map(|l| re.captures(l).map(|c| c.extract().1)) {
+        net.add_labelled_edge(name, left);
+        net.add_labelled_edge(name, right);
+    }
     (dirs, net)
 }
 
+fn step(net: &Network, node: NodeId, dir: &Direction) -> NodeId {
+    match dir {
+        Direction::Left => net.successors(node)[0],
+        Direction::Right => net.successors(node)[1],
+    }
+}
 
 pub fn part_one(input: &str) -> Option<u32> {
     let (dirs, net) = parse(input);
 
-    let mut curr = net.iter().position(|Node{name, ..}| name == "AAA").unwrap();
+    let mut curr = net.id("AAA").unwrap();
     let mut n = 0u32;
-    while net[curr].name != "ZZZ" {
-        curr = match dirs[(n as usize) % dirs.len()] {
-            Direction::Left => net[curr].left,
-            Direction::Right => net[curr].right,
-        };
+    while *net.label(curr) != "ZZZ" {
+        curr = step(&net, curr, &dirs[(n as usize) % dirs.len()]);
         n += 1;
     }
     Some(n)
@@ -60,24 +51,21 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u64> {
     let (dirs, net) = parse(input);
 
-    let curr = net.iter().enumerate().filter(|(_, Node{name, ..})| {
-        name.ends_with('A')
-    }).map(|(idx, _)| idx).collect::<Vec<_>>();
+    // every ghost walks a deterministic path through (node, instruction) states, so it eventually
+    // loops, and the steps at which it is on a Z node repeat with its loop.
+    let ghosts = net.nodes().filter(|&id| net.label(id).ends_with('A')).map(|start| {
+        find_hits(
+            (start, 0),
+            |(node, i)| {
+                *node = step(&net, *node, &dirs[*i]);
+                *i = (*i + 1) % dirs.len();
+            },
+            |&(node, _)| net.label(node).ends_with('Z'),
+        )
+    }).collect::<Vec<_>>();
 
-    // assume for now that LCM works...
-    let res = curr.iter().map(|c| {
-        let mut x = *c;
-        let mut n = 0u64;
-        while !net[x].name.ends_with('Z') {
-            x = match dirs[(n as usize) % dirs.len()] {
-                Direction::Left => net[x].left,
-                Direction::Right => net[x].right,
-            };
-            n += 1;
-        }
-        n
-    }).fold(1, num::integer::lcm);
-    Some(res)
+    let res = first_common_hit(&ghosts).unwrap_or_else(|e| panic!("the ghosts never meet: {e}"));
+    Some(res as u64)
 }
 
 #[cfg(test)]
@@ -1,34 +1,22 @@
+use advent_of_code::parsing::*;
+use advent_of_code::sequence::Polynomial;
 advent_of_code::solution!(9);
 
 fn parse(input: &str) -> Vec<Vec<i64>> {
-    input
-        .lines()
-        .map(|line| line.split_ascii_whitespace().map(|x| x.parse().unwrap()).collect())
-        .collect()
+    parse_all(input, lines(spaced_numbers)).unwrap()
 }
 
-fn differences(values: &[i64]) -> Vec<i64> {
-    let mut result = Vec::with_capacity(values.len() - 1);
-    for i in 0..values.len() - 1 {
-        result.push(values[i + 1] - values[i]);
-    }
-    result
+/// The polynomial behind a sequence, through all of its values if they are too few to tell.
+fn polynomial(seq: &[i64]) -> Polynomial {
+    Polynomial::fit(seq).unwrap_or_else(|| Polynomial::interpolate(seq))
 }
 
 pub fn part_one(input: &str) -> Option<i64> {
     let values = parse(input);
 
     let result = values.into_iter().map(|seq| {
-        let mut diffs = Vec::new();
-        diffs.push(seq);
-        while !diffs.last().unwrap().iter().all(|x| *x == 0) {
-            let last = diffs.last().unwrap();
-            let diff = differences(last);
-            diffs.push(diff);
-        }
-        diffs.into_iter().rev().skip(1).fold(0, |acc, x| {
-            acc + x.last().unwrap()
-        })
+        let poly = polynomial(&seq);
+        poly.eval(seq.len() as i128) as i64
     }).sum();
     Some(result)
 }
@@ -37,16 +25,8 @@ pub fn part_two(input: &str) -> Option<i64> {
     let values = parse(input);
 
     let result = values.into_iter().map(|seq| {
-        let mut diffs = Vec::new();
-        diffs.push(seq);
-        while !diffs.last().unwrap().iter().all(|x| *x == 0) {
-            let last = diffs.last().unwrap();
-            let diff = differences(last);
-            diffs.push(diff);
-        }
-        diffs.into_iter().rev().skip(1).fold(0, |acc, x| {
-            x.first().unwrap() - acc
-        })
+        let poly = polynomial(&seq);
+        poly.eval(-1) as i64
     }).sum();
     Some(result)
 }
@@ -66,4 +46,11 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2));
     }
+
+    #[test]
+    fn test_short_sequences() {
+        // `1 2` continues with 3 and starts from 0, `5` stays constant.
+        assert_eq!(part_one("1 2\n5"), Some(8));
+        assert_eq!(part_two("1 2\n5"), Some(5));
+    }
 }
@@ -1,37 +1,8 @@
-advent_of_code::solution!(10);
-
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-enum Direction {
-    N = 0,
-    S = 1,
-    W = 2,
-    E = 3,
-}
+use advent_of_code::direction::Direction;
+use advent_of_code::grid::Grid;
+use advent_of_code::polygon::Polygon;
 
-impl std::convert::TryFrom<i32> for Direction {
-    type Error = ();
-
-    fn try_from(v: i32) -> Result<Self, Self::Error> {
-        match v {
-            x if x == Direction::N as i32 => Ok(Direction::N),
-            x if x == Direction::S as i32 => Ok(Direction::S),
-            x if x == Direction::W as i32 => Ok(Direction::W),
-            x if x == Direction::E as i32 => Ok(Direction::E),
-            _ => Err(()),
-        }
-    }
-}
-
-fn opposite(dir: Direction) -> Direction {
-    match dir {
-        Direction::N => Direction::S,
-        Direction::S => Direction::N,
-        Direction::W => Direction::E,
-        Direction::E => Direction::W,
-    }
-}
-
-type Grid<T> = ndarray::Array2<T>;
+advent_of_code::solution!(10);
 
 #[derive(Debug, Clone, Copy)]
 enum Node {
@@ -46,11 +17,11 @@ struct Map {
 }
 
 trait MapTrait {
-    fn main_loop(&self) -> MapLoop;
+    fn main_loop(&self) -> MapLoop<'_>;
 }
 
 impl MapTrait for Map {
-    fn main_loop(&self) -> MapLoop {
+    fn main_loop(&self) -> MapLoop<'_> {
         MapLoop {
             map: self,
             curr: Some(self.start),
@@ -90,27 +61,9 @@ impl Iterator for MapLoop<'_> {
     }
 }
 
-fn advance(pos: (usize, usize), size: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
-    let (i, j) = pos;
-    let (di, dj) = match dir {
-        Direction::N => (-1, 0),
-        Direction::S => (1, 0),
-        Direction::W => (0, -1),
-        Direction::E => (0, 1),
-    };
-    let (ni, nj) = (i as isize + di, j as isize + dj);
-    
-    if ni < 0 || ni >= size.0 as isize || nj < 0 || nj >= size.1 as isize {
-        None
-    }
-    else {
-        Some((ni as usize, nj as usize))
-    }
-}
-
 fn neighbours(map: &Map, node: (usize, usize)) -> Vec<(usize, usize)> {
     if let Node::Connection(a, b) = map.grid[node] {
-        [a, b].into_iter().filter_map(|dir| advance(node, (map.grid.nrows(), map.grid.ncols()), dir)).collect()
+        [a, b].into_iter().filter_map(|dir| map.grid.step(node, dir)).collect()
     }
     else {
         vec![]
@@ -125,43 +78,27 @@ fn connects(node: &Node, dir: Direction) -> bool {
 }
 
 fn parse(input: &str) -> Map {
-    let mut nodes = Vec::new();
-    let mut width = 0;
-    let mut height = 0;
     let mut start = (0, 0);
 
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            let v = match c {
-                'S' => {
-                    start = (i, j);
-                    Node::Empty
-                },
-                '.' => Node::Empty,
-                '|' => Node::Connection(Direction::N, Direction::S),
-                '-' => Node::Connection(Direction::W, Direction::E),
-
-                'J' => Node::Connection(Direction::N, Direction::W),
-                'L' => Node::Connection(Direction::N, Direction::E),
-
-                '7' => Node::Connection(Direction::S, Direction::W),
-                'F' => Node::Connection(Direction::S, Direction::E),
-                _ => panic!("unexpected character"),
-            };
-            nodes.push(v);
-        }
-        if width == 0 {
-            width = line.len();
-        } else if width != line.len() {
-            panic!("inconsistent line length");
-        }
-        height += 1;
-    }
-
-    let mut grid = Grid::from_shape_vec((height, width), nodes).unwrap();
-    let conns = (0..4).map(|d| Direction::try_from(d).unwrap()).map(|dir| {
-        if let Some(n) = advance(start, (height, width), dir) {
-            let conn = connects(&grid[n], opposite(dir));
+    let mut grid = Grid::parse_indexed(input, |idx, c| match c {
+        'S' => {
+            start = idx;
+            Some(Node::Empty)
+        },
+        '.' => Some(Node::Empty),
+        '|' => Some(Node::Connection(Direction::N, Direction::S)),
+        '-' => Some(Node::Connection(Direction::W, Direction::E)),
+
+        'J' => Some(Node::Connection(Direction::N, Direction::W)),
+        'L' => Some(Node::Connection(Direction::N, Direction::E)),
+
+        '7' => Some(Node::Connection(Direction::S, Direction::W)),
+        'F' => Some(Node::Connection(Direction::S, Direction::E)),
+        _ => None,
+    }).unwrap();
+    let conns = Direction::CARDINAL.into_iter().map(|dir| {
+        if let Some(n) = grid.step(start, dir) {
+            let conn = connects(&grid[n], dir.opposite());
             (dir, conn)
         }
         else {
@@ -186,34 +123,48 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let map = parse(input);
 
-    let mut is_loop = Grid::<bool>::from_elem((map.grid.nrows(), map.grid.ncols()), false);
-    for idx in map.main_loop() {
-        is_loop[idx] = true;
-    }
-    let is_loop = is_loop;
-
-    let result = (0..map.grid.nrows()).map(|i| {
-        map.grid.row(i).indexed_iter().fold((0u32, 0u32), |(mut acc, mut intersections), (j, n)|{
-            if is_loop[(i, j)] {
-                if let Node::Connection(a, _) = n {
-                    if *a == Direction::N {
-                        intersections += 1;
-                    }
-                }
-            }
-            else if intersections % 2 == 1 {
-                acc += 1;
-            }
-            (acc, intersections)
-        }).0
-    }).sum();
-
-    Some(result)
+    // the enclosed tiles are the lattice points inside the loop through the centers of its tiles
+    let main_loop = Polygon::from_vertices(map.main_loop().map(|(i, j)| (i as i64, j as i64)));
+    Some(main_loop.interior_points() as u32)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::bits::BitGrid;
+    use advent_of_code::flood::components_fine;
+
+    /// The enclosed tiles found by flooding the outside instead. Every pipe of the loop is drawn
+    /// across 3x3 sub-tiles, so that the fill can squeeze between pipes, and the enclosed tiles are
+    /// the ones the outside never reaches.
+    fn count_enclosed_by_flood_fill(input: &str) -> u32 {
+        let map = parse(input);
+        let mut on_loop = BitGrid::new(map.grid.size());
+        for pos in map.main_loop() {
+            on_loop.insert(pos);
+        }
+
+        let components = components_fine(&map.grid, 3, |pos, node, (di, dj)| {
+            if !on_loop.contains(pos) {
+                return true;
+            }
+            let Node::Connection(a, b) = node else { unreachable!("the loop only goes through pipes") };
+            // the pipe goes from the center towards both of its connections.
+            (di, dj) != (1, 1) && [a, b].into_iter().all(|d| {
+                let (i, j) = d.delta();
+                ((1 + i) as usize, (1 + j) as usize) != (di, dj)
+            })
+        });
+        components.enclosed().map(|l| components.sizes()[l]).sum::<usize>() as u32
+    }
+
+    #[test]
+    fn test_flood_fill_matches_pick() {
+        for k in 3..=6 {
+            let input = advent_of_code::template::read_file_part("examples", DAY, k);
+            assert_eq!(part_two(&input), Some(count_enclosed_by_flood_fill(&input)));
+        }
+    }
 
     #[test]
     fn test_part_one_a() {
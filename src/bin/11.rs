@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use advent_of_code::point::Point2;
 
 advent_of_code::solution!(11);
 
@@ -36,20 +37,17 @@ fn solve(input: &str, k: usize) -> Option<usize> {
     let empty_cols = (0..map.first().unwrap().len()).filter(|c| map.iter().map(|row| &row[*c]).all(|n| *n == Node::Empty) ).collect::<Vec<_>>();
 
     let galaxies = map.iter().enumerate().flat_map(|(r, row)| {
-        row.iter().enumerate().filter(|(_, n)| **n == Node::Galaxy).map(move |(c,_)| (r, c))
+        row.iter().enumerate().filter(|(_, n)| **n == Node::Galaxy).map(move |(c,_)| Point2::new(r, c))
     }).collect::<Vec<_>>();
 
-    let result = galaxies.iter().combinations(2).map(|group| {
-        let (r0, c0) = &group[0];
-        let (r1, c1) = &group[1];
-
-        let (rmin, rmax) = (*r0.min(r1), *r0.max(r1));
-        let (cmin, cmax) = (*c0.min(c1), *c0.max(c1));       
+    let result = galaxies.iter().tuple_combinations().map(|(&a, &b)| {
+        let Point2 { x: rmin, y: cmin } = a.min(b);
+        let Point2 { x: rmax, y: cmax } = a.max(b);
 
         let rs = empty_rows.partition_point(|r| *r < rmax) - empty_rows.partition_point(|r| *r <= rmin);
         let cs = empty_cols.partition_point(|c| *c < cmax) - empty_cols.partition_point(|c| *c <= cmin);
 
-        r0.abs_diff(*r1) + c0.abs_diff(*c1) + (rs+cs) * (k-1)
+        a.manhattan(b) + (rs+cs) * (k-1)
     }).sum();
     Some(result)
 }
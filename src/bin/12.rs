@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use advent_of_code::memo::{DenseCache, Memo};
 
 advent_of_code::solution!(12);
 
@@ -34,21 +35,15 @@ fn parse(input: &str) -> Vec<Entry> {
 }
 
 
-type State = (usize, usize);
-type Mem = std::collections::HashMap<State, usize>;
+type Mem = Memo<(usize, usize), usize, DenseCache<(usize, usize), usize>>;
 
 fn count(mem: &mut Mem, e: &Entry, i: usize, g: usize) -> usize {
     let (vs, groups) = e;
-    let k = (i, g);
-    if let Some(r) = mem.get(&k) {
-        return *r;
-    }
-
-    let result = (||{
+    mem.get_or_compute((i, g), |mem| {
         // check if we have already processed all groups
         if g >= groups.len() {
             // check there are no remaining broken machine
-            if i < vs.len() && vs[i..].iter().any(|v| *v == Machine::Broken) {
+            if i < vs.len() && vs[i..].contains(&Machine::Broken) {
                 return 0;
             }
             else {
@@ -66,7 +61,7 @@ fn count(mem: &mut Mem, e: &Entry, i: usize, g: usize) -> usize {
         // we need to fit group g at current position i
         let mut result = 0;
         if vs[i..(i+grp)].iter().all(|v| *v != Machine::Operational) 
-            && vs.get(i+grp).map_or(true, |v| *v != Machine::Broken)
+            && vs.get(i+grp).is_none_or(|v| *v != Machine::Broken)
         {
             result += count(mem, e, i + grp + 1, g + 1);
         }
@@ -77,16 +72,13 @@ fn count(mem: &mut Mem, e: &Entry, i: usize, g: usize) -> usize {
         }
 
         result
-    })();
-
-    mem.insert(k, result);
-
-    result
+    })
 }
 
 fn solve(input: &[Entry]) -> usize {
     input.iter().map(|s|{
-        let mut mem = Mem::new();
+        // a group can end one past the last machine, which moves i to len + 1.
+        let mut mem = Mem::dense((s.0.len() + 2, s.1.len() + 1));
         count(&mut mem, s, 0, 0)
     }).sum()
 }
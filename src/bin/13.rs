@@ -1,4 +1,6 @@
-use itertools::Itertools;
+use ndarray::ArrayView2;
+use advent_of_code::grid::{Grid, Transform};
+use advent_of_code::parsing::*;
 
 advent_of_code::solution!(13);
 
@@ -8,55 +10,35 @@ enum Node {
     Rock,
 }
 
-type Map = Vec<Vec<Node>>;
+type Map = Grid<Node>;
 
 fn parse(input: &str) -> Vec<Map> {
-    input.lines().collect_vec().split(|s| s.is_empty()).map(|lines|{
-        lines.iter().map(|row| {
-            row.chars().map(|c| match c {
-                '.' => Node::Ash,
-                '#' => Node::Rock,
-                _ => panic!("unexpected symbol"),
-            }).collect()
-        }).collect()
-    }).collect()
+    parse_all(input, blocks(grid(|c| match c {
+        '.' => Some(Node::Ash),
+        '#' => Some(Node::Rock),
+        _ => None,
+    }))).unwrap()
 }
 
-fn find_mirror(m: &Map) -> Option<usize> {
-    (1..m.len()).find(|r| 
-        m[0..*r].iter().rev().zip(m[*r..m.len()].iter())
-        .all(|(a, b)| a == b)
-    )
+fn row_differences(m: &ArrayView2<Node>, a: usize, b: usize) -> usize {
+    m.row(a).iter().zip(m.row(b)).filter(|(a, b)| a != b).count()
 }
 
-fn find_mirror_smudge(m: &Map) -> Option<usize> {
-    (1..m.len()).find(|r| 
-        m[0..*r].iter().rev().zip(m[*r..m.len()].iter())
-        .map(|(a, b)| {
-            a.iter().zip(b).map(|(a, b)| if a == b { 0 } else { 1 }).sum::<u32>()
-        }).sum::<u32>() == 1
+/// The number of rows above a horizontal mirror of `m`, seen through a view so that the same
+/// search also finds vertical mirrors.
+fn find_mirror(m: ArrayView2<Node>, smudges: usize) -> Option<usize> {
+    (1..m.nrows()).find(|r| 
+        (0..*r).rev().zip(*r..m.nrows())
+        .map(|(a, b)| row_differences(&m, a, b))
+        .sum::<usize>() == smudges
     )
 }
 
-fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    assert!(!v.is_empty());
-    let len = v[0].len();
-    let mut iters: Vec<_> = v.into_iter().map(|n| n.into_iter()).collect();
-    (0..len)
-        .map(|_| {
-            iters
-                .iter_mut()
-                .map(|n| n.next().unwrap())
-                .collect::<Vec<T>>()
-        })
-        .collect()
-}
-
 pub fn part_one(input: &str) -> Option<usize> {
     let input = parse(input);
     let r = input.into_iter().map(|m|{
-        find_mirror(&m).map(|x| x*100)
-        .or_else(|| { find_mirror(&transpose(m)) })
+        find_mirror(m.view(Transform::Identity), 0).map(|x| x*100)
+        .or_else(|| { find_mirror(m.view(Transform::Transpose), 0) })
         .unwrap()
     }).sum::<usize>();
     Some(r)
@@ -65,8 +47,8 @@ pub fn part_one(input: &str) -> Option<usize> {
 pub fn part_two(input: &str) -> Option<usize> {
     let input = parse(input);
     let r = input.into_iter().map(|m|{
-        find_mirror_smudge(&m).map(|x| x*100)
-        .or_else(|| { find_mirror_smudge(&transpose(m)) })
+        find_mirror(m.view(Transform::Identity), 1).map(|x| x*100)
+        .or_else(|| { find_mirror(m.view(Transform::Transpose), 1) })
         .unwrap()
     }).sum::<usize>();
     Some(r)
@@ -1,5 +1,8 @@
 use std::fmt::Write;
 use ndarray::prelude::*;
+use advent_of_code::cycle::nth_state;
+use advent_of_code::direction::Direction;
+use advent_of_code::grid::Transform;
 
 advent_of_code::solution!(14);
 
@@ -20,36 +23,15 @@ impl std::fmt::Display for Node {
     }
 }
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-type Grid = Array2<Node>;
+type Grid = advent_of_code::grid::Grid<Node>;
 
 fn parse(input: &str) -> Grid {
-    let mut width = 0;
-    let mut nodes = Vec::new();
-
-    for line in input.lines() {
-        nodes.extend(line.chars().map(|c| match c {
-            '.' => Node::Empty,
-            'O' => Node::Round,
-            '#' => Node::Square,
-            _ => panic!("unexpected symbol"),
-        }));
-
-        let w = line.len();
-        if width == 0 {
-            width = w;
-        }
-        else if w != width {
-            panic!("unexpected line width");
-        }
-    }
-    Grid::from_shape_vec((nodes.len() / width, width), nodes).unwrap()
+    Grid::parse(input, |c| match c {
+        '.' => Some(Node::Empty),
+        'O' => Some(Node::Round),
+        '#' => Some(Node::Square),
+        _ => None,
+    }).unwrap()
 }
 
 fn compute_weight(grid: &Grid) -> usize {
@@ -61,16 +43,9 @@ fn compute_weight(grid: &Grid) -> usize {
     }).sum()
 }
 
-fn tilt(grid: &mut Grid, dir: Direction) {
-    let (axis, node) = match dir {
-        Direction::North => (Axis(1), Node::Round),
-        Direction::South => (Axis(1), Node::Empty),
-        Direction::West  => (Axis(0), Node::Round),
-        Direction::East  => (Axis(0), Node::Empty),
-    };
-
-    for c in 0..grid.len_of(axis) {
-        let mut vs = grid.index_axis_mut(axis, c);
+/// Rolls the round rocks in every row of `view` to the end of the row.
+fn tilt_rows(mut view: ArrayViewMut2<Node>) {
+    for mut vs in view.rows_mut() {
         let n = vs.len();
 
         let mut a = vs.iter().position(|x| *x != Node::Square).unwrap_or(n);
@@ -81,7 +56,7 @@ fn tilt(grid: &mut Grid, dir: Direction) {
                 let mut r = b - 1;
 
                 while l < r {
-                    if vs[r] == node {
+                    if vs[r] == Node::Empty {
                         vs.swap(r, l);
                         l += 1;
                     }
@@ -95,39 +70,25 @@ fn tilt(grid: &mut Grid, dir: Direction) {
     }
 }
 
+fn tilt(grid: &mut Grid, dir: Direction) {
+    tilt_rows(grid.view_mut(Transform::rows_toward(dir)));
+}
+
 fn cycle(grid: &mut Grid) {
-    for d in [Direction::North, Direction::West, Direction::South, Direction::East] {
+    for d in [Direction::N, Direction::W, Direction::S, Direction::E] {
         tilt(grid, d);
     }
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
     let mut map = parse(input);
-    tilt(&mut map, Direction::North);
+    tilt(&mut map, Direction::N);
     Some(compute_weight(&map))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     const K: usize = 1_000_000_000;
-    let mut map = parse(input);
-
-    let mut mem = std::collections::HashMap::new();
-    for curr in 0..K {
-        if let Some(prev) = mem.get(&map) {
-            let n = curr - prev;
-            let remaining = (K - curr) % n;
-            for _ in 0..remaining {
-                cycle(&mut map);
-            }
-            break;
-        }
-        else {
-            let k = map.clone();
-            cycle(&mut map);
-            mem.insert(k, curr);
-        }
-    }
-
+    let map = nth_state(parse(input), cycle, K);
     Some(compute_weight(&map))
 }
 
@@ -1,4 +1,7 @@
-use std::{fmt::Write, collections::HashSet};
+use std::fmt::Write;
+use advent_of_code::bits::{BitGrid, BitSet};
+use advent_of_code::direction::Direction;
+use advent_of_code::point::Point2;
 
 advent_of_code::solution!(16);
 
@@ -24,111 +27,85 @@ impl std::fmt::Display for Node {
     }
 }
 
-type Grid = ndarray::Array2<Node>;
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    North = 0,
-    East = 1,
-    South = 2,
-    West = 3,
-}
+type Grid = advent_of_code::grid::Grid<Node>;
 
 fn parse(input: &str) -> Grid {
-    let mut width = 0;
-    let mut nodes = Vec::new();
-    for line in input.lines() {
-        nodes.extend(line.chars().map(|c| match c {
-            '.' => Node::Empty,
-            '|' => Node::SplitV,
-            '-' => Node::SplitH,
-            '\\' => Node::SE_NW, // E->S; S->E; N->W; W->N 
-            '/' => Node::SW_NE,
-            _ => panic!("unexpected character {:?}", c),
-        }));
-        if width == 0 {
-            width = line.len();
-        }
-        else if width != line.len() {
-            panic!("inconsistent widths")
-        }
-    }
-
-    Grid::from_shape_vec((nodes.len() / width, width), nodes).unwrap()
+    Grid::parse(input, |c| match c {
+        '.' => Some(Node::Empty),
+        '|' => Some(Node::SplitV),
+        '-' => Some(Node::SplitH),
+        '\\' => Some(Node::SE_NW), // E->S; S->E; N->W; W->N 
+        '/' => Some(Node::SW_NE),
+        _ => None,
+    }).unwrap()
 }
 
-fn advance((i, j): (isize, isize), direction: Direction) -> (isize, isize) {
-    match direction {
-        Direction::East => (i, j+1),
-        Direction::North => (i-1, j),
-        Direction::South => (i+1, j),
-        Direction::West => (i, j-1),
-    }
-}
+type Pos = Point2<isize>;
 
-fn is_vertical(direction: Direction) -> bool {
-    matches!(direction, Direction::North | Direction::South)
+/// The beams seen so far, one bit per cell and cardinal direction.
+fn beam_index(grid: &Grid, (i, j): (usize, usize), direction: Direction) -> usize {
+    let d = direction.cardinal_index().expect("beams only travel in cardinal directions");
+    (i * grid.ncols() + j) * 4 + d
 }
 
-type EnergyGrid = ndarray::Array2<bool>;
-fn energize_rec(grid: &Grid, energized: &mut EnergyGrid, (i, j): (isize, isize), direction: Direction, mem: &mut HashSet<(isize, isize, Direction)>) {
-    if i < 0 || i >= grid.nrows() as isize || j < 0 || j >= grid.ncols() as isize { return; }
-    if mem.contains(&(i,j,direction)) { return; }
-    mem.insert((i,j, direction));
-    let c = (i as usize, j as usize);
-    energized[c] = true;
+fn energize_rec(grid: &Grid, energized: &mut BitGrid, pos: Pos, direction: Direction, mem: &mut BitSet) {
+    if !grid.contains(pos.into()) { return; }
+    let c = pos.to_index().unwrap();
+    if !mem.insert(beam_index(grid, c, direction)) { return; }
+    energized.insert(c);
 
-    let mut adv = |d: Direction| energize_rec(grid, energized, advance((i,j), d), d, mem);
+    let mut adv = |d: Direction| energize_rec(grid, energized, pos + d.into(), d, mem);
 
     match (grid[c], direction) {
         (Node::Empty, d) => adv(d), 
-        (Node::SplitV, d) if is_vertical(d) => adv(d),
-        (Node::SplitH, d) if !is_vertical(d) => adv(d),
+        (Node::SplitV, d) if d.is_vertical() => adv(d),
+        (Node::SplitH, d) if d.is_horizontal() => adv(d),
         (Node::SplitV, _) => {
-            adv(Direction::North);
-            adv(Direction::South);
+            adv(Direction::N);
+            adv(Direction::S);
         },
         (Node::SplitH, _) => {
-            adv(Direction::East);
-            adv(Direction::West);
+            adv(Direction::E);
+            adv(Direction::W);
         },
-        (Node::SW_NE, Direction::South) => adv(Direction::West),
-        (Node::SW_NE, Direction::West) => adv(Direction::South),
-        (Node::SW_NE, Direction::North) => adv(Direction::East),
-        (Node::SW_NE, Direction::East) => adv(Direction::North),
-        (Node::SE_NW, Direction::South) => adv(Direction::East),
-        (Node::SE_NW, Direction::East) => adv(Direction::South),
-        (Node::SE_NW, Direction::North) => adv(Direction::West),
-        (Node::SE_NW, Direction::West) => adv(Direction::North),
-
+        (Node::SW_NE, Direction::S) => adv(Direction::W),
+        (Node::SW_NE, Direction::W) => adv(Direction::S),
+        (Node::SW_NE, Direction::N) => adv(Direction::E),
+        (Node::SW_NE, Direction::E) => adv(Direction::N),
+        (Node::SE_NW, Direction::S) => adv(Direction::E),
+        (Node::SE_NW, Direction::E) => adv(Direction::S),
+        (Node::SE_NW, Direction::N) => adv(Direction::W),
+        (Node::SE_NW, Direction::W) => adv(Direction::N),
+        _ => unreachable!("beams only travel in cardinal directions"),
     }
 }
-fn energize(grid: &Grid, start: (isize, isize), direction: Direction) -> EnergyGrid {
-    let mut result = EnergyGrid::from_elem((grid.nrows(), grid.ncols()), false);
-    energize_rec(grid, &mut result, start, direction, &mut HashSet::new());
+fn energize(grid: &Grid, start: Pos, direction: Direction) -> BitGrid {
+    let mut result = BitGrid::new(grid.size());
+    energize_rec(grid, &mut result, start, direction, &mut BitSet::new(grid.len() * 4));
     result
 }
 
-fn count_energize(grid: &Grid, start: (isize, isize), direction: Direction) -> u32 {
-    energize(grid, start, direction).into_iter().map(u32::from).sum::<u32>()
+fn count_energize(grid: &Grid, start: Pos, direction: Direction) -> u32 {
+    energize(grid, start, direction).count() as u32
 }
 
 
 pub fn part_one(input: &str) -> Option<u32> {
     let grid = parse(input);
-    Some(count_energize(&grid, (0,0), Direction::East))
+    Some(count_energize(&grid, Pos::new(0, 0), Direction::E))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let grid = parse(input);
     let mut result = 0;
+    let start = |i, j| Pos::from_index((i, j)).unwrap();
     for i in 0..grid.nrows() {
-        result = result.max(count_energize(&grid, (i as isize, 0), Direction::East));
-        result = result.max(count_energize(&grid, (i as isize, (grid.ncols() - 1) as isize), Direction::West));
+        result = result.max(count_energize(&grid, start(i, 0), Direction::E));
+        result = result.max(count_energize(&grid, start(i, grid.ncols() - 1), Direction::W));
     }
     for j in 0..grid.ncols() {
-        result = result.max(count_energize(&grid, (0, j as isize), Direction::South));
-        result = result.max(count_energize(&grid, ((grid.nrows() - 1) as isize, j as isize), Direction::North));
+        result = result.max(count_energize(&grid, start(0, j), Direction::S));
+        result = result.max(count_energize(&grid, start(grid.nrows() - 1, j), Direction::N));
     }
     Some(result)
 }
@@ -1,7 +1,8 @@
-use std::{collections::HashMap, ops::RangeBounds};
+use std::collections::HashMap;
 
-use nom::{IResult, branch::alt, multi::{separated_list1, many1}, character::complete::{char, alpha1, digit1, line_ending}, Parser, sequence::{tuple, delimited, separated_pair, preceded, terminated}, bytes::complete::tag};
-use ranges::{Ranges, GenericRange};
+use advent_of_code::parsing::*;
+use advent_of_code::interval::IntervalSet;
+use advent_of_code::memo::Memo;
 advent_of_code::solution!(19);
 
 #[derive(Debug, Clone, Copy)]
@@ -12,7 +13,7 @@ enum PartType {
     S,
 }
 
-fn parse_part_type(input: &str) -> IResult<&str, PartType> {
+fn parse_part_type(input: &str) -> PResult<'_, PartType> {
     alt((
         char('x').map(|_| PartType::X),
         char('m').map(|_| PartType::M),
@@ -27,7 +28,7 @@ enum Comparison {
     GreaterThan,
 }
 
-fn parse_comparison(input: &str) -> IResult<&str, Comparison> {
+fn parse_comparison(input: &str) -> PResult<'_, Comparison> {
     alt((
         char('<').map(|_| Comparison::LessThan),
         char('>').map(|_| Comparison::GreaterThan),
@@ -41,7 +42,7 @@ enum Goto {
     Label(String),
 }
 
-fn parse_goto(input: &str) -> IResult<&str, Goto> {
+fn parse_goto(input: &str) -> PResult<'_, Goto> {
     alt((
         char('A').map(|_| Goto::Accept),
         char('R').map(|_| Goto::Reject),
@@ -57,11 +58,11 @@ struct Condition {
     num: u32,
 }
 
-fn parse_condition(input: &str) -> IResult<&str, Condition> {
+fn parse_condition(input: &str) -> PResult<'_, Condition> {
     (tuple((
         parse_part_type,
         parse_comparison,
-        digit1.map(|v: &str| v.parse::<u32>().unwrap())
+        unsigned
     ))
     .map(|(part, comparison, num)| Condition {part, comparison, num}))
     .parse(input)    
@@ -75,7 +76,7 @@ struct Workflow {
     process: Vec<Process>,
 }
 
-fn parse_workflow(input: &str) -> IResult<&str, Workflow> {
+fn parse_workflow(input: &str) -> PResult<'_, Workflow> {
     tuple((
         alpha1.map(|s: &str| s.to_string()),
         delimited(char('{'), separated_list1(
@@ -97,23 +98,23 @@ struct Part {
     s: u32,
 }
 
-fn parse_part(input: &str) -> IResult<&str, Part> {
+fn parse_part(input: &str) -> PResult<'_, Part> {
     delimited(char('{'), tuple((
-        delimited(tag("x="), digit1.map(|s: &str| s.parse::<u32>().unwrap()), char(',')),
-        delimited(tag("m="), digit1.map(|s: &str| s.parse::<u32>().unwrap()), char(',')),
-        delimited(tag("a="), digit1.map(|s: &str| s.parse::<u32>().unwrap()), char(',')),
-        preceded(tag("s="), digit1.map(|s: &str| s.parse::<u32>().unwrap()))
+        delimited(tag("x="), unsigned, char(',')),
+        delimited(tag("m="), unsigned, char(',')),
+        delimited(tag("a="), unsigned, char(',')),
+        preceded(tag("s="), unsigned)
     )), char('}'))
     .map(|(x, m, a, s)| Part {x, m, a, s})
     .parse(input)
 }
 
 fn parse(input: &str) -> (Vec<Workflow>, Vec<Part>) {
-    separated_pair(
-        many1(terminated(parse_workflow, line_ending)),
-        line_ending,
-        many1(terminated(parse_part, line_ending))
-    ).parse(input).unwrap().1
+    parse_all(input, separated_pair(
+        lines(parse_workflow),
+        blank_line,
+        lines(parse_part)
+    )).unwrap()
 }
 
 
@@ -161,46 +162,29 @@ pub fn part_one(input: &str) -> Option<u32> {
 
 #[derive(Clone, Debug)]
 struct PartRange {
-    x: Ranges<u32>,
-    m: Ranges<u32>,
-    a: Ranges<u32>,
-    s: Ranges<u32>,
-}
-
-fn range_length(r: &GenericRange<u32>) -> usize {
-    let b = match r.start_bound() {
-        std::ops::Bound::Unbounded => 1,
-        std::ops::Bound::Included(x) => *x,
-        std::ops::Bound::Excluded(x) => *x + 1,
-    };
-
-    let e = match r.end_bound() {
-        std::ops::Bound::Unbounded => 4000,
-        std::ops::Bound::Included(x) => *x + 1,
-        std::ops::Bound::Excluded(x) => *x,
-    };
-    (e - b) as usize
-}
-
-fn ranges_length(r: &Ranges<u32>) -> usize {
-    r.as_slice().iter().map(range_length).sum()
+    x: IntervalSet<u32>,
+    m: IntervalSet<u32>,
+    a: IntervalSet<u32>,
+    s: IntervalSet<u32>,
 }
 
 impl PartRange {
     fn all() -> Self {
-        PartRange { x: Ranges::from(1..=4000), m: Ranges::from(1..=4000), a: Ranges::from(1..=4000), s: Ranges::from(1..=4000) }
+        let all = IntervalSet::from(1..4001);
+        PartRange { x: all.clone(), m: all.clone(), a: all.clone(), s: all }
     }
 
     fn split(value: &Condition) -> (Self, Self) {
         let range = match value.comparison {
-            Comparison::GreaterThan => Ranges::from((value.num+1)..),
-            Comparison::LessThan => Ranges::from(..value.num),
+            Comparison::GreaterThan => IntervalSet::from((value.num+1)..4001),
+            Comparison::LessThan => IntervalSet::from(1..value.num),
         };
+        let inv = IntervalSet::from(1..4001).difference(&range);
         match value.part {
-            PartType::X => (PartRange { x: range.clone(), ..PartRange::all() }, PartRange { x: range.invert(), ..PartRange::all() }),
-            PartType::M => (PartRange { m: range.clone(), ..PartRange::all() }, PartRange { m: range.invert(), ..PartRange::all() }),
-            PartType::A => (PartRange { a: range.clone(), ..PartRange::all() }, PartRange { a: range.invert(), ..PartRange::all() }),
-            PartType::S => (PartRange { s: range.clone(), ..PartRange::all() }, PartRange { s: range.invert(), ..PartRange::all() }),
+            PartType::X => (PartRange { x: range, ..PartRange::all() }, PartRange { x: inv, ..PartRange::all() }),
+            PartType::M => (PartRange { m: range, ..PartRange::all() }, PartRange { m: inv, ..PartRange::all() }),
+            PartType::A => (PartRange { a: range, ..PartRange::all() }, PartRange { a: inv, ..PartRange::all() }),
+            PartType::S => (PartRange { s: range, ..PartRange::all() }, PartRange { s: inv, ..PartRange::all() }),
         }
     }
 
@@ -210,14 +194,14 @@ impl PartRange {
 
     fn intersect(self, other: PartRange) -> Self {
         PartRange {
-            x: self.x & other.x,
-            m: self.m & other.m,
-            a: self.a & other.a,
-            s: self.s & other.s,
+            x: self.x.intersection(&other.x),
+            m: self.m.intersection(&other.m),
+            a: self.a.intersection(&other.a),
+            s: self.s.intersection(&other.s),
         }
     }
     fn size(&self) -> usize {
-        ranges_length(&self.x) * ranges_length(&self.m) * ranges_length(&self.a) * ranges_length(&self.s)
+        self.x.len() as usize * self.m.len() as usize * self.a.len() as usize * self.s.len() as usize
     }
 }
 
@@ -227,12 +211,8 @@ impl std::fmt::Display for PartRange {
     }
 }
 
-fn part_range(label: &str, map: &HashMap<String, Vec<Process>>, mem: &mut HashMap<String, Vec<PartRange>>) -> Vec<PartRange> {
-    if let Some(range) = mem.get(label) {
-        return range.clone()
-    }
-
-    let result: Vec<PartRange> = {
+fn part_range(label: &str, map: &HashMap<String, Vec<Process>>, mem: &mut Memo<String, Vec<PartRange>>) -> Vec<PartRange> {
+    mem.get_or_compute(label.to_string(), |mem| {
         let mut result = Vec::new();
         let mut mask = PartRange::all();
 
@@ -264,10 +244,7 @@ fn part_range(label: &str, map: &HashMap<String, Vec<Process>>, mem: &mut HashMa
         }
 
         result
-    };
-
-    mem.insert(label.to_string(), result.clone());
-    result
+    })
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
@@ -276,7 +253,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     for w in ws {
         map.insert(w.label, w.process);
     }
-    let result = part_range("in", &map, &mut HashMap::new()).into_iter()
+    let result = part_range("in", &map, &mut Memo::new()).into_iter()
         // .map(|r| { println!("{}",r); r })
         .map(|r| r.size()).sum();
     Some(result)
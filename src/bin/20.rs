@@ -1,5 +1,8 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 use itertools::Itertools;
+use advent_of_code::crt::first_common_hit;
+use advent_of_code::cycle::find_hits_by_key;
+use advent_of_code::graph::{Graph, NodeId};
 
 advent_of_code::solution!(20);
 
@@ -9,25 +12,18 @@ enum ModuleState {
     Conjunction(Vec<bool>),
 }
 
-#[derive(Debug)]
-struct Connection {
-    name: String,
-    output: Vec<Result<usize, String>>,
-    input: Vec<usize>,
-}
-
 #[derive(Debug)]
 struct Modules {
-    broadcaster: usize,
-    rx_in: Option<usize>,
-    connections: Vec<Connection>,
+    broadcaster: NodeId,
+    rx_in: Option<NodeId>,
+    graph: Graph<String>,
     state: Vec<Option<ModuleState>>,
+    /// which modules sent a high pulse during the last button press
+    sent_high: Vec<bool>,
 }
 
 fn parse(input: &str) -> Modules {
-    let mut name_map = HashMap::new();
-
-    let mut output = Vec::new();
+    let mut graph = Graph::new();
     let mut state = Vec::new();
 
     for line in input.lines() {
@@ -39,83 +35,74 @@ fn parse(input: &str) -> Modules {
             _ => (None, left),
         };
 
-        name_map.insert(left.to_string(), name_map.len());
-
-        output.push(Vec::from_iter(right.split(", ").map(String::from)));
-
-        state.push(s);
-    }
-
-    let output = output.into_iter().map(|ns| {
-        ns.into_iter().map(|v| name_map.get(&v).cloned().ok_or(v)).collect_vec()
-    }).collect_vec();
-
-    let mut input = vec![Vec::new(); output.len()];
-    for (i, out) in output.iter().enumerate() {
-        for j in out.iter().flatten() {
-            let v = &mut input[*j];
-
-            let p = v.partition_point(|&x| x < i);
-            v.insert(p, i);
-            assert!(v.windows(2).all(|a| a[0] <= a[1] ));
+        let id = graph.node(left.to_string());
+        for out in right.split(", ") {
+            let out = graph.node(out.to_string());
+            graph.add_edge(id, out);
         }
-    }
 
-    let mut connections = input.into_iter().zip(output).map(|(input, output)|{
-        Connection { name: "".to_string(), output, input }
-    }).collect_vec();
+        state.resize(graph.len(), None);
+        state[id] = s;
+    }
+    // modules that only appear as outputs, like rx, have no state
+    state.resize(graph.len(), None);
 
     for (i, s) in state.iter_mut().enumerate() {
         if let Some(ModuleState::Conjunction(vs)) = s.as_mut() {
-            *vs = vec![false; connections[i].input.len()];
+            *vs = vec![false; graph.predecessors(i).len()];
         }
     }
 
-    let broadcaster = *name_map.get("broadcaster").unwrap();
+    let broadcaster = graph.id("broadcaster").unwrap();
 
-    for (name, idx) in name_map {
-        connections[idx].name = name;
-    }
+    let rx_in = graph.id("rx").map(|rx| graph.predecessors(rx)[0]);
 
-    let rx_in = connections.iter().position(|x| x.output.iter().any(|o| o.as_ref().is_err_and(|o| o == "rx")) );
+    let sent_high = vec![false; graph.len()];
 
-    Modules { rx_in, broadcaster, connections, state }
+    Modules { rx_in, broadcaster, graph, state, sent_high }
 }
 
 impl Modules {
+    /// All modules whose pulses can reach `module`, including itself.
+    fn upstream(&self, module: NodeId) -> Vec<NodeId> {
+        let mut result = self.graph.reversed().bfs(module);
+        result.sort();
+        result
+    }
+
     fn signal(&mut self) -> (usize, usize) {
         let mut signals = VecDeque::new();
         let mut low_count = 1; // from aptly to broadcaster
         let mut high_count = 0;
+        self.sent_high.fill(false);
 
-        for out in &self.connections[self.broadcaster].output {
-            signals.push_back((self.broadcaster, out.clone(), false));
+        for &out in self.graph.successors(self.broadcaster) {
+            signals.push_back((self.broadcaster, out, false));
         }
 
         while let Some((from, to, is_high)) = signals.pop_front() {
             if is_high { high_count += 1; } else { low_count += 1; }
 
-            if let Ok(to) = to {
-                if let Some(state) = &mut self.state[to] {
-                    let send_signal = match state {
-                        ModuleState::Flipflop(v) => {
-                            if !is_high {
-                                *v = !*v; // flip state
-                                Some(*v)
-                            }
-                            else { None }
-                        },
-                        ModuleState::Conjunction(vs) => {
-                            let i = self.connections[to].input.binary_search(&from).unwrap();
-                            vs[i] = is_high;
-                            let all_high = is_high && vs.iter().all(|v| *v);
-                            Some(!all_high)
-                        },
-                    };
-                    if let Some(h) = send_signal {
-                        for out in &self.connections[to].output {
-                            signals.push_back((to, out.clone(), h));
+            if let Some(state) = &mut self.state[to] {
+                let send_signal = match state {
+                    ModuleState::Flipflop(v) => {
+                        if !is_high {
+                            *v = !*v; // flip state
+                            Some(*v)
                         }
+                        else { None }
+                    },
+                    ModuleState::Conjunction(vs) => {
+                        let i = self.graph.predecessors(to).iter().position(|&x| x == from).unwrap();
+                        vs[i] = is_high;
+                        let all_high = is_high && vs.iter().all(|v| *v);
+                        Some(!all_high)
+                    },
+                };
+                if let Some(h) = send_signal {
+                    self.sent_high[to] |= h;
+                    for &out in self.graph.successors(to) {
+                        signals.push_back((to, out, h));
                     }
                 }
             }
@@ -141,60 +128,24 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut modules = parse(input);
+    let modules = parse(input);
 
     assert!(modules.rx_in.is_some());
     let rx = modules.rx_in.unwrap();
 
-    let mut freq = vec![vec![0usize]; modules.connections[rx].input.len()];
-    for pulses in 1.. {
-        let mut signals = VecDeque::new();
-        for out in &modules.connections[modules.broadcaster].output {
-            signals.push_back((modules.broadcaster, out.clone(), false));
-        }
-
-        while let Some((from, to, is_high)) = signals.pop_front() {
-            if let Ok(to) = to {
-                if let Some(state) = &mut modules.state[to] {
-                    let send_signal = match state {
-                        ModuleState::Flipflop(v) => {
-                            if !is_high {
-                                *v = !*v; // flip state
-                                Some(*v)
-                            }
-                            else { None }
-                        },
-                        ModuleState::Conjunction(vs) => {
-                            let i = modules.connections[to].input.binary_search(&from).unwrap();
-                            vs[i] = is_high;
-                            let all_high = is_high && vs.iter().all(|v| *v);
-                            Some(!all_high)
-                        },
-                    };
-                    if let Some(h) = send_signal {
-                        for out in &modules.connections[to].output {
-                            if to == rx {
-                                if let Some(ModuleState::Conjunction(vs)) = &modules.state[to] {
-                                    for (i, b) in vs.iter().enumerate() {
-                                        if *b && freq[i].last().cloned() != Some(pulses) {
-                                            freq[i].push(pulses);
-                                        }
-                                    }
-                                }
-                            }
-                            signals.push_back((to, out.clone(), h));
-                        }
-                    }
-                }
-            }
-        }
+    // every input of the conjunction in front of rx is driven by its own part of the network,
+    // which cycles independently from the others. rx gets a low pulse once all of them send a
+    // high pulse during the same press, so find the presses at which each one does and line them
+    // up. the sent pulses are part of the key, as they depend on the previous state.
+    let walkers = modules.graph.predecessors(rx).iter().map(|&i| {
+        let upstream = modules.upstream(i);
+        find_hits_by_key(parse(input), |m| { m.signal(); }, |m| {
+            (upstream.iter().map(|&u| m.state[u].clone()).collect_vec(), m.sent_high[i])
+        }, |m| m.sent_high[i])
+    }).collect_vec();
+    let result = first_common_hit(&walkers).unwrap_or_else(|e| panic!("rx never gets a low pulse: {e}"));
 
-        if freq.iter().all(|x| x.len() > 1) {
-            let result = freq.iter().map(|x| x[x.len() - 1] - x[x.len() - 2]).fold(1, num::integer::lcm);
-            return Some(result);
-        }
-    }
-    None
+    Some(result)
 }
 
 #[cfg(test)]
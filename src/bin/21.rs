@@ -1,8 +1,11 @@
-use std::collections::VecDeque;
 use std::fmt::Write;
-use ndarray::prelude::*;
 use itertools::Itertools;
-use num::Integer;
+
+use advent_of_code::flood::flood_fill;
+use advent_of_code::point::Point2;
+use advent_of_code::search;
+use advent_of_code::sequence::Polynomial;
+use advent_of_code::tiled::Tiled;
 
 advent_of_code::solution!(21);
 
@@ -21,69 +24,39 @@ impl std::fmt::Display for Node {
     }
 }
 
-type Grid = Array2<Node>;
-type Input = ([i32; 2], Grid);
+type Grid = advent_of_code::grid::Grid<Node>;
+/// A plot in grid coordinates: `x` is the row and `y` the column.
+type Pos = Point2<i32>;
+type Input = (Pos, Grid);
 fn parse(input: &str) -> Input {
-    let mut width = 0;
-    let mut nodes = Vec::new();
     let mut start = None;
-
-    for (i, line) in input.lines().enumerate() {
-        if width == 0 {
-            width = line.len();
-        }
-        else if width != line.len() {
-            panic!("unexpected line width");
-        }
-
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '.' => nodes.push(Node::Empty),
-                '#' => nodes.push(Node::Blocked),
-                'S' => {
-                    assert!(start.is_none());
-                    nodes.push(Node::Empty);
-                    start = Some([i as i32, j as i32]);
-                }
-                _ => panic!("unexpected char"),
-            }
+    let grid = Grid::parse_indexed(input, |(i, j), c| match c {
+        '.' => Some(Node::Empty),
+        '#' => Some(Node::Blocked),
+        'S' => {
+            assert!(start.is_none());
+            start = Some(Pos::from_index((i, j)).unwrap());
+            Some(Node::Empty)
         }
-    }
-
-    (start.unwrap(), Grid::from_shape_vec((nodes.len() / width, width), nodes).unwrap() )
+        _ => None,
+    }).unwrap();
 
+    (start.unwrap(), grid)
 }
 
-fn bfs_distance(start: &[i32; 2], grid: &Grid) -> Array2<u32> {
-    let mut distance = ndarray::Array::from_elem(grid.raw_dim(), u32::MAX);
-
-    let mut queue = VecDeque::new();
-    queue.push_front((*start, 0));
-
-    while let Some(([i, j], d)) = queue.pop_front() {
-        let out_of_bounds = i < 0 || j < 0 || i as usize >= grid.nrows() || j as usize >= grid.ncols();
-        let idx = [i as usize, j as usize];
-        if out_of_bounds || distance[idx] <= d || grid[idx] == Node::Blocked {
-            continue;
-        }
-
-        distance[idx] = d;
-        queue.push_back(([i+1, j], d+1));
-        queue.push_back(([i-1, j], d+1));
-        queue.push_back(([i, j+1], d+1));
-        queue.push_back(([i, j-1], d+1));
-    }
-
-    distance
+fn bfs_distance(start: &Pos, grid: &Grid) -> advent_of_code::grid::Grid<u32> {
+    let start = start.to_index().unwrap();
+    search::grid_bfs_distances(grid, [start], |_, n| *n != Node::Blocked)
+        .map(|d| d.map_or(u32::MAX, |d| d as u32))
 }
 
-fn count(start: &[i32; 2], grid: &Grid, steps: usize) -> u32 {
+fn count(start: &Pos, grid: &Grid, steps: usize) -> u32 {
     let distance = bfs_distance(start, grid);
-    let start = start.map(|v| v as usize);
+    let start = start.try_cast::<usize>().unwrap();
     let parity = steps % 2;
 
-    distance.indexed_iter().filter(|(p, d)| {
-        **d <= steps as u32 && (p.0.abs_diff(start[0]) + p.1.abs_diff(start[1])) % 2 == parity
+    distance.indexed_iter().filter(|&(p, d)| {
+        *d <= steps as u32 && Point2::from(p).manhattan(start) % 2 == parity
     }).count() as u32
 }
 
@@ -92,82 +65,20 @@ fn part_one_impl(input: &str, steps: usize) -> Option<u32> {
     Some(count(&start, &grid, steps))
 }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////////
-
-// fn bfs_distance_modular(start: &[i32; 2], grid: &Grid, step: usize) -> Array2<u32> {
-//     let n = step * 2 + 1;
-//     let mut distance = ndarray::Array::from_elem((n, n), u32::MAX);
-    
-//     // start is at the center of distance, distance grid has an offset wrt to grid (0,0)
-//     let offset = [start[0] - step as i32, start[1] - step as i32];
-
-//     let mut queue = VecDeque::new();
-//     queue.push_front(([step as i32, step as i32], 0));
-
-//     while let Some(([i, j], d)) = queue.pop_front() {
-//         let out_of_bounds = i < 0 || j < 0 || i as usize >= n || j as usize >= n;
-//         let idx = [i as usize, j as usize];
-//         let grid_idx = [(i + offset[0]).mod_floor(&(grid.nrows() as i32)) as usize, (j + offset[1]).mod_floor(&(grid.ncols() as i32)) as usize];
-//         if out_of_bounds || distance[idx] <= d || grid[grid_idx] == Node::Blocked {
-//             continue;
-//         }
-
-//         distance[idx] = d;
-//         queue.push_back(([i+1, j], d+1));
-//         queue.push_back(([i-1, j], d+1));
-//         queue.push_back(([i, j+1], d+1));
-//         queue.push_back(([i, j-1], d+1));
-//     }
-
-//     distance
-// }
-
-// fn count_modular_2(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
-//     let distance = bfs_distance_modular(start, grid, steps); // start is centered at [step, step]
-//     let parity = steps % 2;
-
-//     distance.indexed_iter().filter(|(p, d)| {
-//         **d <= steps as u32 && (p.0.abs_diff(steps) + p.1.abs_diff(steps)) % 2 == parity
-//     }).count()
-// }
-
-fn count_modular(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
-    let parity = steps % 2;
-
-    let mut queue = VecDeque::new();
-    queue.push_front((*start, 0));
-
-    let mut processed = std::collections::HashSet::new();
-
-    let mut result = 0;
-    while let Some(([i, j], d)) = queue.pop_front() {
-
-        if processed.contains(&[i, j]) { continue; }
-        processed.insert([i, j]);
-        
-        let grid_idx = [
-            i.mod_floor(&(grid.nrows() as i32)) as usize, 
-            j.mod_floor(&(grid.ncols() as i32)) as usize
-        ];
-        if d > steps || grid[grid_idx] == Node::Blocked {
-            continue;
-        }
-        let diff = (i.abs_diff(start[0]) + j.abs_diff(start[1])) as usize;
-        if (diff % 2) == parity {
-            result += 1;
-        }
-
-        queue.push_back(([i+1, j], d+1));
-        queue.push_back(([i-1, j], d+1));
-        queue.push_back(([i, j+1], d+1));
-        queue.push_back(([i, j-1], d+1));
-    }
-
-    result
+/// The number of plots of the infinitely tiled garden reachable in exactly each of `steps`, from a
+/// single search to the largest of them.
+fn count_modular<const N: usize>(start: &Pos, grid: &Grid, steps: [usize; N]) -> [usize; N] {
+    let tiled = Tiled::new(grid);
+    let start = start.try_cast::<isize>().unwrap().into();
+    let max = steps.iter().copied().max().unwrap_or(0);
+    let distances = tiled.bfs_distances(start, max, |n| *n != Node::Blocked);
+
+    // plots are reachable in exactly `s` steps when they are within `s` and one can step back and
+    // forth for the remaining ones.
+    steps.map(|s| distances.count(|d| d <= s && d % 2 == s % 2))
 }
 
-#[allow(dead_code)]
-fn extrapolate(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
+fn extrapolate(start: &Pos, grid: &Grid, steps: usize) -> usize {
     let w = grid.ncols();
     let h = grid.nrows();
     assert_eq!(w, h);
@@ -178,34 +89,17 @@ fn extrapolate(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
         let s = steps % n;
         let m = steps / n;
 
-        let vs = [
-            count_modular(start, grid, s),
-            count_modular(start, grid, s + n),
-            count_modular(start, grid, s + 2*n)
-        ];
-
-        // we do newtown polynomials, where the value we want to get is m
-        let a = [
-            vs[0] as isize,
-            (vs[1] - vs[0]) as isize,
-            (((vs[2] - vs[1]) - (vs[1] - vs[0])) / 2) as isize,
-        ];
-
-        let poly = |x| a[0] + a[1] * x + a[2] * x * (x - 1);
-
-        assert_eq!(poly(0) as usize, vs[0]);
-        assert_eq!(poly(1) as usize, vs[1]);
-        assert_eq!(poly(2) as usize, vs[2]);
-        
-        poly(m as isize) as usize
+        let vs = count_modular(start, grid, [s, s + n, s + 2*n]);
+
+        let poly = Polynomial::interpolate(&vs.map(|v| v as u64));
+        poly.eval(m as i128) as usize
     }
     else {
-        count_modular(start, grid, steps)
+        count_modular(start, grid, [steps])[0]
     }
 }
 
-#[allow(dead_code)]
-fn count_big_border(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {   
+fn count_big_border(start: &Pos, grid: &Grid, steps: usize) -> usize {   
     // we know that the input has some trenches of empty space
     // in the outer border, and so the minimum period between maps
     // is just the width/height of the initial map
@@ -239,20 +133,21 @@ fn count_big_border(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
     // a similar argument can be done for any direction, and when combining both vertical and horizontal movement
     // then the choice is the concrete corner
 
-    let ul = bfs_distance(&[0, 0], grid);
-    let bl = bfs_distance(&[(h - 1) as i32, 0], grid);
-    let ur = bfs_distance(&[0, (w - 1) as i32], grid);
-    let br = bfs_distance(&[(h - 1) as i32, (w - 1) as i32], grid);
+    let ul = bfs_distance(&Pos::new(0, 0), grid);
+    let bl = bfs_distance(&Pos::new((h - 1) as i32, 0), grid);
+    let ur = bfs_distance(&Pos::new(0, (w - 1) as i32), grid);
+    let br = bfs_distance(&Pos::new((h - 1) as i32, (w - 1) as i32), grid);
 
-    let start = (start[0] as usize, start[1] as usize);
+    let start = start.to_index().unwrap();
 
     let parity = steps % 2;
-    ul.indexed_iter().filter_map(|(p, n)| { (*n != u32::MAX).then_some(p) })
+    // only the plots reachable from the corners count, some are walled in.
+    flood_fill(grid, [(0, 0)], |_, n| *n != Node::Blocked).iter()
         .map(|p| {
             let mut result = 0;
 
             // map (0,0)
-            let correct_parity = parity == (p.0.abs_diff(start.0) + p.1.abs_diff(start.1)) % 2;
+            let correct_parity = parity == Point2::from(p).manhattan(start.into()) % 2;
             if correct_parity { result += 1; }
 
             // map moving to one of the main directions:
@@ -281,102 +176,7 @@ fn count_big_border(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {
                 let m = 1 + (steps - offset as usize) / d;
                 if correct_parity {
                     // we sum up all odd numbers
-                    let odd = (m+1) / 2;
-                    result += odd * odd;
-                }
-                else {
-                    // we sum up all even numbers
-                    let even = m / 2;
-                    result += even * (even + 1);
-
-                }    
-            }
-
-            result
-        }).sum()
-}
-
-#[allow(dead_code)]
-fn count_big_cross(start: &[i32; 2], grid: &Grid, steps: usize) -> usize {   
-    // we know that the input has some trenches of empty space
-    // in the outer border and centered on start, and so the minimum period between maps
-    // is just the width/height of the initial map
-
-    let w = grid.ncols();
-    let h = grid.nrows();
-    assert_eq!(w, h);
-    let d = w;
-
-    // Given a cell (i,j), we reach their clones whenever we move from a map to another.
-    // We index each map with (I,J).
-    // Given that maps are odd, the parity of the cell changes accordingly.
-    // When moving N maps at, say, direction (1,0),
-    // then that cell is within
-    //      d(S, c) = argmin (d(S, B0) + N*d(B0, B1) + d(B1, c))
-    // where B0 correspond to a cell in the bottom trench and B1 a cell in the bottom trench of next bottom map
-
-    // we also know that h = w, and so it is the same in terms of period to move one direction or the other
-
-    // at a really big distance (N > w), the term d(B0, B1) dominates and so only those columns totally
-    // free are to be considered, and by construction this map has the cross and the middle rows as empty
-
-    assert!(steps > 2*w); // sanity check
-
-    let empty_rows = grid.rows().into_iter().enumerate().filter_map(|(i, m)| m.iter().all(|n| *n == Node::Empty).then_some(i)).collect_vec();
-    assert_eq!(&empty_rows, &[0, start[0] as usize, d - 1]);
-    let empty_cols = grid.columns().into_iter().enumerate().filter_map(|(j, m)| m.iter().all(|n| *n == Node::Empty).then_some(j)).collect_vec();
-    assert_eq!(&empty_cols, &[0, start[1] as usize, d - 1]);
-
-    // this makes the choice of B0 and B1 to be easy, as there are only one possibility the row/column of start
-    // a similar argument can be done when moving to a corner
-
-    let last = (d-1) as i32;
-    let ul = bfs_distance(&[0, 0], grid);
-    let bl = bfs_distance(&[last, 0], grid);
-    let ur = bfs_distance(&[0, last], grid);
-    let br = bfs_distance(&[last, last], grid);
-    let l = bfs_distance(&[start[0], 0], grid);
-    let r = bfs_distance(&[start[0], last], grid);
-    let u = bfs_distance(&[0, start[1]], grid);
-    let b = bfs_distance(&[last, start[1]], grid);
-
-    let start = (start[0] as usize, start[1] as usize);
-    let parity = steps % 2;
-    ul.indexed_iter().filter_map(|(p, d)| { (*d != u32::MAX).then_some(p) })
-        .map(|p| {
-            let mut result = 0;
-
-            // map (0,0)
-            let correct_parity = parity == (p.0.abs_diff(start.0) + p.1.abs_diff(start.1)) % 2;
-            if correct_parity { result += 1; }
-
-            // map moving to one of the main directions:
-            for offset in [
-                b[start] + u[p] + 1, // moving down
-                u[start] + b[p] + 1, // moving up
-                l[start] + r[p] + 1, // moving right
-                r[start] + l[p] + 1, // moving left
-            ]
-            {
-                if steps < offset as usize { continue; }
-                let n = 1 + (steps - offset as usize) / d;
-                result += n / 2; // only half of the maps will have correct parity
-                if n % 2 == 1 && !correct_parity { result += 1; }    
-            }
-
-            // map moving to one of the main diagonals:
-            for offset in [
-                br[start] + ul[p] + 2,
-                ur[start] + bl[p] + 2,
-                bl[start] + ur[p] + 2,
-                ul[start] + br[p] + 2,
-            ]
-            {
-                if steps < offset as usize { continue; }
-                let m = 1 + (steps - offset as usize) / d;
-                if correct_parity {
-                    // we sum up all odd numbers
-                    let odd = (m+1) / 2;
+                    let odd = m.div_ceil(2);
                     result += odd * odd;
                 }
                 else {
@@ -395,10 +195,9 @@ fn part_two_impl(input: &str, steps: usize) -> Option<usize> {
     let (start, grid) = parse(input);
     let result = {
         if steps < 100 {
-            count_modular(&start, &grid, steps)
+            count_modular(&start, &grid, [steps])[0]
         }
-        else if grid.column(start[1] as usize).iter().all(|n| *n == Node::Empty) {
-            //count_big_cross(&start, &grid, steps)
+        else if grid.column(start.to_index().unwrap().1).iter().all(|n| *n == Node::Empty) {
             extrapolate(&start, &grid, steps)
         }
         else {
@@ -1,36 +1,31 @@
 use ndarray::prelude::*;
+use advent_of_code::dag::Dag;
+use advent_of_code::point::{Box3, Point3};
+use advent_of_code::scan::integers;
 
 advent_of_code::solution!(22);
 
-type Brick = [[usize; 3]; 2];
+type Brick = Box3<usize>;
 
 fn parse(input: &str) -> Vec<Brick> {
-    input.lines().map(|line| {
-        let (left, right) = line.split_once('~').unwrap();
-        let mut left_it = left.split(',').map(|x| x.parse::<usize>().unwrap());
-        let mut right_it = right.split(',').map(|x| x.parse::<usize>().unwrap());
-
-        [
-            [left_it.next().unwrap(), left_it.next().unwrap(), left_it.next().unwrap()],
-            [right_it.next().unwrap(), right_it.next().unwrap(), right_it.next().unwrap()],
-        ]
+    integers(input.as_bytes()).arrays().map(|[x0, y0, z0, x1, y1, z1]| {
+        Box3::new(Point3::new(x0, y0, z0), Point3::new(x1, y1, z1))
     }).collect()
 }
 
 fn settle(bricks: &mut [Brick]) -> Vec<Vec<usize>> {
-    bricks.sort_by_key(|v| v[0][2] );
+    bricks.sort_by_key(|b| b.min.z);
 
-    let xy = bricks.iter().fold((0,0), |(x, y), b| {
-        (x.max(b[1][0]), y.max(b[1][1]))
-    });
-    let xy = (xy.0 + 1, xy.1 + 1);
+    let xy = bricks.iter().fold(Point3::default(), |p, b| p.max(b.max));
+    let xy = (xy.x + 1, xy.y + 1);
 
     let mut floor = ndarray::Array2::from_elem(xy, (None, 0));
 
     let mut result = Vec::new();
-    for (idx, [[x0, y0, z0], [x1, y1, z1]]) in bricks.iter_mut().enumerate() {
-        let z = *z1 - *z0 + 1;
-        let (supporting, at_z) = floor.slice(s![ *x0..=*x1, *y0..=*y1 ]).iter()
+    for (idx, b) in bricks.iter().enumerate() {
+        let z = b.extent().z;
+        let area = s![ b.min.x..=b.max.x, b.min.y..=b.max.y ];
+        let (supporting, at_z) = floor.slice(area).iter()
             .fold((vec![], 0), |(mut s, curr_z), (which, z)|{
                 if curr_z <= *z {
                     if curr_z < *z { s.clear(); }
@@ -43,7 +38,7 @@ fn settle(bricks: &mut [Brick]) -> Vec<Vec<usize>> {
                 }
             });
         
-        for c in floor.slice_mut(s![ *x0..=*x1, *y0..=*y1 ]) {
+        for c in floor.slice_mut(area) {
             *c = (Some(idx), at_z + z);
         }
 
@@ -53,69 +48,100 @@ fn settle(bricks: &mut [Brick]) -> Vec<Vec<usize>> {
     result
 }
 
-fn count_falling(brick: usize, supporting: &mut Vec<Vec<usize>>, supported_by: &mut Vec<Vec<usize>>) -> u32 {
-    let mut result = 0;
-
-    let mut chain = Vec::new();
-    for i in std::mem::take(&mut supporting[brick]) {
-        let idx = supported_by[i].iter().position(|x| *x == brick);
-        supported_by[i].remove(idx.unwrap());
-        if supported_by[i].is_empty() {
-            chain.push(i);
-        }
-    }
-
-    for brick in chain {
-        result += count_falling(brick, supporting, supported_by) + 1;
+/// The bricks as a graph with an edge from every brick to the ones resting on it, and from the
+/// ground, the node after the bricks, to the ones resting on it.
+fn supports(bricks: &mut [Brick]) -> Dag {
+    let mut supported_by = settle(bricks);
+    let ground = supported_by.len();
+    for s in supported_by.iter_mut().filter(|s| s.is_empty()) {
+        s.push(ground);
     }
+    supported_by.push(vec![]);
+    Dag::from_predecessors(&supported_by).expect("bricks only rest on lower ones")
+}
 
-    result
+/// Whether removing the brick leaves every brick on it supported by another one.
+fn is_safe(supports: &Dag, brick: usize) -> bool {
+    supports.successors(brick).all(|b| supports.predecessors(b).count() > 1)
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let mut bricks = parse(input);
-    let supported_by = settle(&mut bricks);
-
-    let mut supporting = std::collections::HashSet::new();
-    for s in supported_by {
-        if s.len() == 1 {
-            supporting.insert(s[0]);
-        }
-    }
+    let supports = supports(&mut bricks);
 
-    Some((bricks.len() - supporting.len()) as u32)
+    Some((0..bricks.len()).filter(|&b| is_safe(&supports, b)).count() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let mut bricks = parse(input);
-    let supported_by = settle(&mut bricks);
+    let supports = supports(&mut bricks);
+
+    // a brick falls without another one when every way down to the ground goes through it, i.e.
+    // when the other one dominates it.
+    let dominators = supports.dominators(bricks.len());
+    let falling = (0..bricks.len()).map(|b| dominators.subtree_size(b) - 1).sum::<usize>();
+
+    Some(falling as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut spof = std::collections::HashSet::new();
-    for s in supported_by.iter() {
-        if s.len() == 1 {
-            spof.insert(s[0]);
+    fn count_falling(brick: usize, supporting: &mut Vec<Vec<usize>>, supported_by: &mut Vec<Vec<usize>>) -> u32 {
+        let mut result = 0;
+
+        let mut chain = Vec::new();
+        for i in std::mem::take(&mut supporting[brick]) {
+            let idx = supported_by[i].iter().position(|x| *x == brick);
+            supported_by[i].remove(idx.unwrap());
+            if supported_by[i].is_empty() {
+                chain.push(i);
+            }
         }
-    }
 
-    // revert the graph
-    let mut supporting = vec![vec![]; supported_by.len()];
-    for (idx, s) in supported_by.iter().enumerate() {
-        for i in s {
-            supporting[*i].push(idx);
+        for brick in chain {
+            result += count_falling(brick, supporting, supported_by) + 1;
         }
-    }
 
-    let mut result = 0;
-    for b in spof {
-        result += count_falling(b, &mut supporting.clone(), &mut supported_by.clone());
+        result
     }
 
-    Some(result)
-}
+    /// Part two by removing every brick that is the only support of another one, and letting the
+    /// bricks above it fall one at a time.
+    fn part_two_naive(input: &str) -> u32 {
+        let mut bricks = parse(input);
+        let supported_by = settle(&mut bricks);
+
+        let mut supporting = vec![vec![]; supported_by.len()];
+        for (idx, s) in supported_by.iter().enumerate() {
+            for i in s {
+                supporting[*i].push(idx);
+            }
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let spof = supported_by.iter().filter(|s| s.len() == 1).map(|s| s[0]).collect::<std::collections::HashSet<_>>();
+        spof.into_iter().map(|b| count_falling(b, &mut supporting.clone(), &mut supported_by.clone())).sum()
+    }
+
+    /// A pile of random bricks in a small area, so that they rest on each other a lot.
+    fn random_bricks(n: usize, seed: u64) -> String {
+        let mut state = seed;
+        let mut next = |bound: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) % bound) as usize
+        };
+        (0..n).map(|k| {
+            let (x, y, z) = (next(8), next(8), 1 + k + next(4));
+            let length = next(4);
+            let end = match next(3) {
+                0 => (x + length, y, z),
+                1 => (x, y + length, z),
+                _ => (x, y, z + length),
+            };
+            format!("{x},{y},{z}~{},{},{}", end.0, end.1, end.2)
+        }).collect::<Vec<_>>().join("\n")
+    }
 
     #[test]
     fn test_part_one() {
@@ -128,4 +154,12 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(7));
     }
+
+    #[test]
+    fn test_part_two_matches_naive() {
+        for seed in 0..5 {
+            let input = random_bricks(300, seed);
+            assert_eq!(part_two(&input), Some(part_two_naive(&input)));
+        }
+    }
 }
@@ -1,122 +1,88 @@
-use ndarray::prelude::*;
+use std::collections::HashMap;
+use advent_of_code::bits::BitGrid;
+use advent_of_code::dag::Dag;
+use advent_of_code::direction::Direction;
+use advent_of_code::graph::NodeId;
+use advent_of_code::point::Point2;
 
 advent_of_code::solution!(23);
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
-
 #[derive(Debug, PartialEq, Eq)]
 enum Node {
     Empty,
     Blocked,
-    Slope(Dir),
+    Slope(Direction),
 }
 
 
-fn opposite(dir: Dir) -> Dir {
-    match dir {
-        Dir::N => Dir::S,
-        Dir::S => Dir::N,
-        Dir::E => Dir::W,
-        Dir::W => Dir::E,
-    }
-}
-
-fn clockwise(dir: Dir) -> Dir {
-    match dir {
-        Dir::N => Dir::E,
-        Dir::E => Dir::S,
-        Dir::S => Dir::W,
-        Dir::W => Dir::N,
-    }
-}
+type Grid = advent_of_code::grid::Grid<Node>;
+type Pos = Point2<usize>;
 
-fn advance((h, w): (usize, usize), (ci, cj): (usize, usize), dir: Dir) -> Option<(usize, usize)> {
-    let (di, dj) = match dir {
-        Dir::N => (-1, 0),
-        Dir::E => (0, 1),
-        Dir::S => (1, 0),
-        Dir::W => (0, -1),
-    };
-
-    let i = (ci as i32) + di;
-    let j = (cj as i32) + dj;
-    
-    (i >= 0 && j >= 0 && i < (h as i32) && j < (w as i32)).then_some((i as usize, j as usize))
+fn parse(input: &str) -> Grid {
+    Grid::parse(input, |c| match c {
+        '.' => Some(Node::Empty),
+        '#' => Some(Node::Blocked),
+        '>' | '^' | '<' | 'v' => Direction::try_from(c).ok().map(Node::Slope),
+        _ => None,
+    }).unwrap()
 }
 
-type Grid = Array2<Node>;
-
-fn parse(input: &str) -> Grid {
-    let mut nodes = Vec::new();
-    let mut width = 0;
-    for line in input.lines() {
-        if width == 0 {
-            width = line.len();
-        }
-        else if width != line.len() {
-            panic!("unexpected line width");
+/// The moves out of `idx` other than going back against `dir`, walking slopes only downhill.
+fn exits(idx: Pos, dir: Direction, grid: &Grid) -> Vec<(Pos, Direction)> {
+    let mut result = Vec::new();
+    let mut d = dir.opposite();
+    for _ in 0..3 {
+        d = d.clockwise();
+        if let Some(neighbour) = grid.step(idx.into(), d) {
+            let add = match &grid[neighbour] {
+                Node::Empty => true,
+                Node::Slope(s) if *s != d.opposite() => true,
+                _ => false,
+            };
+            if add {
+                result.push((neighbour.into(), d));
+            }
         }
-
-        nodes.extend(line.chars().map(|c| match c {
-            '.' => Node::Empty,
-            '#' => Node::Blocked,
-            '>' => Node::Slope(Dir::E),
-            '^' => Node::Slope(Dir::N),
-            '<' => Node::Slope(Dir::W),
-            'v' => Node::Slope(Dir::S),
-            _ => panic!("Unexpected character"),
-        }));
     }
-
-    Array2::from_shape_vec((nodes.len() / width, width), nodes).unwrap()
+    result
 }
 
-fn count(start: (usize, usize), end: (usize, usize), dir: Dir, grid: &Grid) -> u32 {
-    let (h, w) = (grid.nrows(), grid.ncols());
-
-    let mut result = 0;
-    let mut ns = [Some((start, dir)), None, None];
-
-    while ns[1].is_none() {
-        let (idx, dir) = ns[0].take().unwrap();
-        if idx == end {
-            return result; // found
-        }
-        
-        let mut i = 0;
+/// The corridors between the start, the end and the junctions where paths split, as a graph
+/// weighted by their lengths. Slopes make every corridor one-way, so there are no cycles.
+fn corridors(start: Pos, end: Pos, dir: Direction, grid: &Grid) -> (Dag<u32>, NodeId, NodeId) {
+    let mut ids = HashMap::from([(start, 0)]);
+    let mut edges = Vec::new();
+    let mut stack = vec![(start, dir)];
+
+    while let Some((junction, dir)) = stack.pop() {
+        for (mut idx, mut dir) in exits(junction, dir, grid) {
+            let mut length = 1;
+            let mut next = exits(idx, dir, grid);
+            while idx != end && next.len() == 1 {
+                (idx, dir) = next[0];
+                length += 1;
+                next = exits(idx, dir, grid);
+            }
+            if idx != end && next.is_empty() {
+                continue; // dead-end
+            }
 
-        let mut d = opposite(dir);
-        for _ in 0..3 {
-            d = clockwise(d);
-            if let Some(neighbour) = advance((h,w), idx, d) {
-                let add = match &grid[neighbour] {
-                    Node::Empty => true,
-                    Node::Slope(s) if *s != opposite(d) => true,
-                    _ => false,
-                };
-                if add {
-                    ns[i] = Some((neighbour, d));
-                    i += 1;
-                }
-            }            
+            let n = ids.len();
+            let id = *ids.entry(idx).or_insert_with(|| {
+                stack.push((idx, dir));
+                n
+            });
+            edges.push((ids[&junction], id, length));
         }
-        result += 1;
     }
 
-    result + ns.into_iter().flatten().map(|(idx, d)| {
-        count(idx, end, d, grid)
-    }).max().unwrap()
+    let n = ids.len();
+    let end = *ids.entry(end).or_insert(n);
+    let dag = Dag::from_weighted_edges(ids.len(), edges).expect("slopes go one way");
+    (dag, 0, end)
 }
 
-
-fn count2(start: (usize, usize), end: (usize, usize), dir: Dir, grid: &Grid, mut visited: Array2<bool>) -> Option<u32> {
-    let (h, w) = (grid.nrows(), grid.ncols());
+fn count2(start: Pos, end: Pos, dir: Direction, grid: &Grid, mut visited: BitGrid) -> Option<u32> {
 
     let mut result = 0;
     let mut ns = [Some((start, dir)), None, None];
@@ -127,17 +93,17 @@ fn count2(start: (usize, usize), end: (usize, usize), dir: Dir, grid: &Grid, mut
             return Some(result); // found
         }
 
-        visited[idx] = true;
+        visited.insert(idx.into());
         
         let mut i = 0;
 
-        let mut d = opposite(dir);
+        let mut d = dir.opposite();
         for _ in 0..3 {
-            d = clockwise(d);
-            if let Some(neighbour) = advance((h,w), idx, d) {
+            d = d.clockwise();
+            if let Some(neighbour) = grid.step(idx.into(), d) {
                 let add = matches!(&grid[neighbour], Node::Empty | Node::Slope(_));
                 if add && !visited[neighbour] {
-                    ns[i] = Some((neighbour, d));
+                    ns[i] = Some((neighbour.into(), d));
                     i += 1;
                 }
             }
@@ -155,12 +121,13 @@ fn count2(start: (usize, usize), end: (usize, usize), dir: Dir, grid: &Grid, mut
 
 pub fn part_one(input: &str) -> Option<u32> {
     let grid = parse(input);
-    Some(count((0, 1), (grid.nrows() - 1, grid.ncols() - 2), Dir::S, &grid))
+    let (dag, start, end) = corridors(Pos::new(0, 1), Pos::new(grid.nrows() - 1, grid.ncols() - 2), Direction::S, &grid);
+    dag.longest_paths(start)[end]
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let grid = parse(input);
-    count2((0, 1), (grid.nrows() - 1, grid.ncols() - 2), Dir::S, &grid, Array2::from_elem((grid.nrows(), grid.ncols()), false))
+    count2(Pos::new(0, 1), Pos::new(grid.nrows() - 1, grid.ncols() - 2), Direction::S, &grid, BitGrid::new(grid.size()))
 }
 
 #[cfg(test)]
@@ -0,0 +1,259 @@
+/// Compact sets of small integers and of grid positions, for the visited sets of searches.
+///
+/// A [`BitSet`] keeps one bit per possible member in `u64` words, so testing and inserting are a
+/// shift and a mask, counting is a popcount per word, and copying a whole set, e.g. for every branch
+/// of a backtracking search, is a single `memcpy`.
+use std::ops::Index;
+
+use crate::grid::Pos;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of the integers below a fixed capacity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    /// An empty set for the integers below `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        BitSet {
+            words: vec![0; capacity.div_ceil(BITS)],
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn locate(&self, i: usize) -> (usize, u64) {
+        assert!(
+            i < self.capacity,
+            "{i} is out of bounds for a set of capacity {}",
+            self.capacity
+        );
+        (i / BITS, 1 << (i % BITS))
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        let (word, mask) = self.locate(i);
+        self.words[word] & mask != 0
+    }
+
+    /// Adds `i`, returning whether it was not in the set yet.
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, mask) = self.locate(i);
+        let added = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        added
+    }
+
+    /// Removes `i`, returning whether it was in the set.
+    pub fn remove(&mut self, i: usize) -> bool {
+        let (word, mask) = self.locate(i);
+        let removed = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of members.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// The members in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(k, &word)| {
+            // peel off the lowest set bit until the word is empty.
+            std::iter::successors(Some(word), |w| Some(w & w.wrapping_sub(1)))
+                .take_while(|&w| w != 0)
+                .map(move |w| k * BITS + w.trailing_zeros() as usize)
+        })
+    }
+
+    /// The underlying words, where bit `b` of word `k` stands for `k * 64 + b`.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Adds every member of `other`, which must have the same capacity.
+    pub fn union_with(&mut self, other: &BitSet) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// Keeps only the members of `other`, which must have the same capacity.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+}
+
+impl Index<usize> for BitSet {
+    type Output = bool;
+
+    fn index(&self, i: usize) -> &bool {
+        if self.contains(i) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+/// A set of the positions of a grid, stored row by row in a [`BitSet`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitSet,
+    shape: (usize, usize),
+}
+
+impl BitGrid {
+    /// An empty set for the positions of a grid of `(rows, columns)`.
+    pub fn new(shape: (usize, usize)) -> Self {
+        BitGrid {
+            bits: BitSet::new(shape.0 * shape.1),
+            shape,
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    fn index_of(&self, (i, j): Pos) -> usize {
+        assert!(
+            i < self.shape.0 && j < self.shape.1,
+            "{:?} is out of bounds for a grid of {:?}",
+            (i, j),
+            self.shape
+        );
+        i * self.shape.1 + j
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.bits.contains(self.index_of(pos))
+    }
+
+    /// Adds `pos`, returning whether it was not in the set yet.
+    pub fn insert(&mut self, pos: Pos) -> bool {
+        self.bits.insert(self.index_of(pos))
+    }
+
+    /// Removes `pos`, returning whether it was in the set.
+    pub fn remove(&mut self, pos: Pos) -> bool {
+        self.bits.remove(self.index_of(pos))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// The number of positions in the set.
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// The positions in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
+        let width = self.shape.1;
+        self.bits.iter().map(move |k| (k / width, k % width))
+    }
+
+    /// The positions as a flat set, see [`BitSet::words`].
+    pub fn bits(&self) -> &BitSet {
+        &self.bits
+    }
+}
+
+impl Index<Pos> for BitGrid {
+    type Output = bool;
+
+    fn index(&self, pos: Pos) -> &bool {
+        &self.bits[self.index_of(pos)]
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BitGrid, BitSet};
+
+    #[test]
+    fn inserts_and_removes() {
+        let mut set = BitSet::new(130);
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+        assert!(set.contains(129) && set[64] && !set[65]);
+        assert_eq!(set.count(), 3);
+        assert_eq!(set.words().len(), 3);
+
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 129]);
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn rejects_values_out_of_bounds() {
+        BitSet::new(64).insert(64);
+    }
+
+    #[test]
+    fn combines_sets() {
+        let mut a = BitSet::new(200);
+        let mut b = BitSet::new(200);
+        for i in (0..200).step_by(2) {
+            a.insert(i);
+        }
+        for i in (0..200).step_by(3) {
+            b.insert(i);
+        }
+        let mut both = a.clone();
+        both.intersect_with(&b);
+        assert_eq!(
+            both.iter().collect::<Vec<_>>(),
+            (0..200).step_by(6).collect::<Vec<_>>()
+        );
+        a.union_with(&b);
+        assert_eq!(a.count(), 100 + 67 - 34);
+    }
+
+    #[test]
+    fn tracks_grid_positions() {
+        let mut grid = BitGrid::new((3, 70));
+        assert!(grid.insert((2, 69)));
+        assert!(grid.insert((1, 0)));
+        assert!(!grid.insert((1, 0)));
+        assert!(grid[(2, 69)] && !grid[(0, 69)]);
+
+        // copies are independent, e.g. for branches of a search.
+        let mut copy = grid.clone();
+        copy.remove((1, 0));
+        assert_eq!(copy.count(), 1);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(1, 0), (2, 69)]);
+    }
+}
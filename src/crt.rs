@@ -0,0 +1,266 @@
+/// The Chinese Remainder Theorem, for finding when several cycles line up.
+///
+/// The moduli do not need to be coprime, as cycle lengths in puzzles rarely are. Arithmetic is done
+/// in `i128`, panicking instead of overflowing.
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::cycle::{Cycle, Hits};
+
+/// The numbers `x` with `x ≡ residue (mod modulus)`, where `0 <= residue < modulus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: i128) -> Self {
+        assert!(modulus > 0, "the modulus must be positive");
+        Congruence {
+            residue: residue.rem_euclid(modulus),
+            modulus,
+        }
+    }
+
+    /// The smallest solution that is at least `min`.
+    pub fn first_from(&self, min: i128) -> i128 {
+        min + (self.residue - min).rem_euclid(self.modulus)
+    }
+
+    /// The numbers that satisfy both congruences, if there are any.
+    pub fn combine(&self, other: &Congruence) -> Option<Congruence> {
+        let (g, p, _) = extended_gcd(self.modulus, other.modulus);
+        let diff = other.residue - self.residue;
+        if diff % g != 0 {
+            return None;
+        }
+        // x = r1 + m1 * k with m1 * k ≡ r2 - r1 (mod m2), where p is the inverse of m1/g modulo m2/g.
+        let m2 = other.modulus / g;
+        let k = ((diff / g) % m2 * p % m2).rem_euclid(m2);
+        let modulus = (self.modulus / g)
+            .checked_mul(other.modulus)
+            .expect("modulus overflows i128");
+        Some(Congruence::new(self.residue + self.modulus * k, modulus))
+    }
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// The numbers that satisfy every congruence, if there are any.
+pub fn solve(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), |acc, c| acc.combine(&c))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlignError {
+    /// No walkers were given.
+    Empty,
+    /// The walker with this index never reaches a goal.
+    NoHits(usize),
+    /// The walkers reach goals, but never all at the same step.
+    NeverAligned,
+}
+
+impl Display for AlignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlignError::Empty => write!(f, "there are no walkers to align"),
+            AlignError::NoHits(i) => write!(f, "walker {i} never reaches a goal"),
+            AlignError::NeverAligned => {
+                write!(f, "the walkers are never at a goal at the same step")
+            }
+        }
+    }
+}
+
+impl Error for AlignError {}
+
+/// The periodic hits of a walker as the steps `x >= offset` with `x ≡ r (mod modulus)` for some
+/// residue `r`, using the smallest modulus that the hits repeat with. A walker that hits every other
+/// step in a long cycle then needs a single congruence instead of one per hit.
+fn periodic_congruences(hits: &Hits) -> (i128, Vec<i128>) {
+    let Cycle { offset, length } = hits.cycle;
+    let set = hits.periodic.iter().collect::<HashSet<_>>();
+    let shifted = |h: usize, p: usize| offset + (h - offset + p) % length;
+    let modulus = (1..=length)
+        .filter(|p| length % p == 0)
+        .find(|&p| hits.periodic.iter().all(|&h| set.contains(&shifted(h, p))))
+        .unwrap();
+    let mut residues = hits
+        .periodic
+        .iter()
+        .map(|&h| (h % modulus) as i128)
+        .collect::<Vec<_>>();
+    residues.sort();
+    residues.dedup();
+    (modulus as i128, residues)
+}
+
+/// The first step at which every walker is at a goal.
+///
+/// Every combination of periodic hits is solved separately, after merging the hits that repeat
+/// within a cycle, so this is meant for walkers with few distinct hits per cycle.
+pub fn first_common_hit(walkers: &[Hits]) -> Result<usize, AlignError> {
+    if walkers.is_empty() {
+        return Err(AlignError::Empty);
+    }
+    if let Some(i) = walkers
+        .iter()
+        .position(|h| h.transient.is_empty() && h.periodic.is_empty())
+    {
+        return Err(AlignError::NoHits(i));
+    }
+
+    // a hit before some cycle starts only happens once, so it is enough to check the others.
+    let transient = walkers
+        .iter()
+        .flat_map(|h| &h.transient)
+        .filter(|&&n| walkers.iter().all(|h| h.contains(n)))
+        .min()
+        .copied();
+
+    // otherwise every walker is in its cycle, at one of its periodic hits.
+    let mut periodic: Option<i128> = None;
+    if walkers.iter().all(|h| !h.periodic.is_empty()) {
+        let congruences = walkers.iter().map(periodic_congruences).collect::<Vec<_>>();
+        let start = walkers.iter().map(|h| h.cycle.offset).max().unwrap() as i128;
+        let mut choice = vec![0; walkers.len()];
+        loop {
+            let combination = congruences
+                .iter()
+                .zip(&choice)
+                .map(|((modulus, residues), &c)| Congruence::new(residues[c], *modulus));
+            if let Some(c) = solve(combination) {
+                let first = c.first_from(start);
+                periodic = Some(periodic.map_or(first, |p| p.min(first)));
+            }
+
+            // move to the next combination of residues.
+            let Some(i) = (0..walkers.len()).find(|&i| choice[i] + 1 < congruences[i].1.len())
+            else {
+                break;
+            };
+            choice[i] += 1;
+            choice[..i].fill(0);
+        }
+    }
+
+    let periodic = periodic.map(|p| usize::try_from(p).expect("common hit overflows usize"));
+    match (transient, periodic) {
+        (Some(a), Some(b)) => Ok(a.min(b)),
+        (Some(a), None) | (None, Some(a)) => Ok(a),
+        (None, None) => Err(AlignError::NeverAligned),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{first_common_hit, solve, AlignError, Congruence};
+    use crate::cycle::{find_hits, Cycle, Hits};
+
+    #[test]
+    fn solves_congruences() {
+        let c = solve([
+            Congruence::new(2, 3),
+            Congruence::new(3, 5),
+            Congruence::new(2, 7),
+        ]);
+        assert_eq!(c, Some(Congruence::new(23, 105)));
+
+        // non-coprime moduli.
+        let c = solve([Congruence::new(3, 4), Congruence::new(5, 6)]);
+        assert_eq!(c, Some(Congruence::new(11, 12)));
+        assert_eq!(solve([Congruence::new(0, 4), Congruence::new(1, 6)]), None);
+
+        assert_eq!(Congruence::new(-1, 5).residue, 4);
+        assert_eq!(Congruence::new(11, 12).first_from(30), 35);
+    }
+
+    fn brute_force(walkers: &[Hits]) -> Option<usize> {
+        (0..10_000).find(|&n| walkers.iter().all(|h| h.contains(n)))
+    }
+
+    #[test]
+    fn aligns_walkers() {
+        // walkers on a ring of `length` states that start `offset` steps before entering it.
+        let walker = |offset: usize, length: usize, goals: &'static [usize]| {
+            find_hits(
+                0,
+                move |x: &mut usize| {
+                    *x = if *x + 1 < offset + length {
+                        *x + 1
+                    } else {
+                        offset
+                    }
+                },
+                move |x| goals.contains(x),
+            )
+        };
+
+        let walkers = [walker(0, 4, &[3]), walker(0, 6, &[5])];
+        assert_eq!(first_common_hit(&walkers), Ok(11));
+
+        // offsets that differ from the cycle lengths, where taking the lcm is wrong.
+        let walkers = [
+            walker(2, 3, &[1, 3]),
+            walker(1, 4, &[2, 4]),
+            walker(0, 5, &[1, 4]),
+        ];
+        assert_eq!(first_common_hit(&walkers).ok(), brute_force(&walkers));
+        assert!(brute_force(&walkers).is_some());
+
+        // a transient hit that the others also hit.
+        let walkers = [walker(5, 2, &[2]), walker(0, 2, &[0])];
+        assert_eq!(first_common_hit(&walkers), Ok(2));
+
+        let walkers = [walker(0, 4, &[1]), walker(0, 6, &[2])];
+        assert_eq!(first_common_hit(&walkers), Err(AlignError::NeverAligned));
+        let walkers = [walker(0, 4, &[1]), walker(0, 6, &[])];
+        assert_eq!(first_common_hit(&walkers), Err(AlignError::NoHits(1)));
+        assert_eq!(first_common_hit(&[]), Err(AlignError::Empty));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for offsets in [[0, 0, 0], [3, 1, 0], [7, 2, 5]] {
+            for lengths in [[4, 6, 10], [3, 5, 7], [8, 12, 9]] {
+                let mut walkers = offsets
+                    .iter()
+                    .zip(lengths)
+                    .enumerate()
+                    .map(|(i, (&offset, length))| Hits {
+                        cycle: Cycle { offset, length },
+                        transient: (0..offset).filter(|n| n % 3 == i).collect(),
+                        periodic: vec![offset + (i + 1) % length, offset + length - 1],
+                    })
+                    .collect::<Vec<_>>();
+                // hits every other step, which is merged into a single congruence.
+                walkers.push(Hits {
+                    cycle: Cycle {
+                        offset: 1,
+                        length: 1000,
+                    },
+                    transient: vec![],
+                    periodic: (1..1001).step_by(2).collect(),
+                });
+                assert_eq!(
+                    first_common_hit(&walkers).ok(),
+                    brute_force(&walkers),
+                    "{offsets:?} {lengths:?}"
+                );
+            }
+        }
+    }
+}
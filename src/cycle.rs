@@ -0,0 +1,271 @@
+/// Cycle detection for "repeat this a billion times" puzzles.
+///
+/// A simulation is described by its initial state and a `step` function that advances a state in
+/// place. Once the states repeat, the state after any number of steps can be computed without
+/// simulating them all.
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The shape of the sequence of states: after `offset` steps, it repeats every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps that leads to the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.offset {
+            n
+        } else {
+            self.offset + (n - self.offset) % self.length
+        }
+    }
+
+    /// The state after `n` steps, by simulating only the reduced number of steps.
+    pub fn nth_state<S>(&self, mut start: S, mut step: impl FnMut(&mut S), n: usize) -> S {
+        for _ in 0..self.reduce(n) {
+            step(&mut start);
+        }
+        start
+    }
+}
+
+/// Finds the cycle by remembering every state.
+pub fn find_cycle<S: Clone + Eq + Hash>(start: S, step: impl FnMut(&mut S)) -> Cycle {
+    find_cycle_by_key(start, step, S::clone)
+}
+
+/// Finds the cycle by remembering a key of every state. States with equal keys are considered equal,
+/// so the key can be a part of a state or a [`fingerprint`] for states that are expensive to store.
+pub fn find_cycle_by_key<S, K: Eq + Hash>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    let mut seen = HashMap::new();
+    for i in 0.. {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(e) => {
+                let offset = *e.get();
+                return Cycle {
+                    offset,
+                    length: i - offset,
+                };
+            }
+            Entry::Vacant(e) => {
+                e.insert(i);
+            }
+        }
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+/// Finds the cycle with Brent's algorithm, which keeps only two states in memory at the cost of
+/// simulating some steps several times.
+pub fn brent<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&mut S)) -> Cycle {
+    // find the length by moving the tortoise to the hare at increasing powers of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    step(&mut hare);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        step(&mut hare);
+        length += 1;
+    }
+
+    // find the offset by moving both, `length` steps apart, until they meet.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        step(&mut hare);
+    }
+    let mut offset = 0;
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        offset += 1;
+    }
+
+    Cycle { offset, length }
+}
+
+/// A 64-bit hash of a state, to be used as key in [`find_cycle_by_key`].
+pub fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The steps at which a walker is at a goal, which repeat with its cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hits {
+    pub cycle: Cycle,
+    /// The hits before the cycle starts, which never happen again.
+    pub transient: Vec<usize>,
+    /// The hits in the first round of the cycle. Each one happens again every `cycle.length` steps.
+    pub periodic: Vec<usize>,
+}
+
+impl Hits {
+    /// Whether the walker is at a goal after `n` steps.
+    pub fn contains(&self, n: usize) -> bool {
+        if n < self.cycle.offset {
+            self.transient.contains(&n)
+        } else {
+            self.periodic.contains(&self.cycle.reduce(n))
+        }
+    }
+}
+
+/// Follows a walker until its states repeat, recording the steps at which it is at a goal.
+pub fn find_hits<S: Clone + Eq + Hash>(
+    start: S,
+    step: impl FnMut(&mut S),
+    is_goal: impl FnMut(&S) -> bool,
+) -> Hits {
+    find_hits_by_key(start, step, S::clone, is_goal)
+}
+
+/// Like [`find_hits`], but states with equal keys are considered equal as in [`find_cycle_by_key`].
+/// Whether a state is a goal must only depend on its key.
+pub fn find_hits_by_key<S, K: Eq + Hash>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Hits {
+    let mut seen = HashMap::new();
+    let mut hits = Vec::new();
+    for i in 0.. {
+        if let Some(&offset) = seen.get(&key(&state)) {
+            let periodic = hits.split_off(hits.partition_point(|&h| h < offset));
+            return Hits {
+                cycle: Cycle {
+                    offset,
+                    length: i - offset,
+                },
+                transient: hits,
+                periodic,
+            };
+        }
+        seen.insert(key(&state), i);
+        if is_goal(&state) {
+            hits.push(i);
+        }
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+/// The state after `n` steps, simulating only until the first repeated state.
+pub fn nth_state<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&mut S), n: usize) -> S {
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = start;
+    for i in 0..n {
+        if let Some(&offset) = seen.get(&state) {
+            let cycle = Cycle {
+                offset,
+                length: i - offset,
+            };
+            return history.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(state.clone(), i);
+        history.push(state.clone());
+        step(&mut state);
+    }
+    state
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, find_cycle, find_cycle_by_key, find_hits, fingerprint, nth_state, Cycle};
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 2 -> ...
+    fn small(x: &mut u32) {
+        *x = if *x == 4 { 2 } else { *x + 1 };
+    }
+
+    fn pseudo_random(x: &mut u64) {
+        *x = (*x * *x + 7) % 1009;
+    }
+
+    fn naive<S>(mut state: S, mut step: impl FnMut(&mut S), n: usize) -> S {
+        for _ in 0..n {
+            step(&mut state);
+        }
+        state
+    }
+
+    #[test]
+    fn finds_offset_and_length() {
+        let expected = Cycle {
+            offset: 2,
+            length: 3,
+        };
+        assert_eq!(find_cycle(0, small), expected);
+        assert_eq!(brent(0, small), expected);
+        assert_eq!(find_cycle_by_key(0, small, fingerprint), expected);
+    }
+
+    #[test]
+    fn variants_agree() {
+        for start in [0, 3, 42, 500] {
+            let cycle = find_cycle(start, pseudo_random);
+            assert_eq!(brent(start, pseudo_random), cycle);
+            assert_eq!(find_cycle_by_key(start, pseudo_random, fingerprint), cycle);
+        }
+    }
+
+    #[test]
+    fn reduces_steps() {
+        let cycle = Cycle {
+            offset: 2,
+            length: 3,
+        };
+        assert_eq!(cycle.reduce(1), 1);
+        assert_eq!(cycle.reduce(5), 2);
+        assert_eq!(cycle.reduce(1_000_000_000), 4);
+        assert_eq!(cycle.nth_state(0, small, 1_000_000_000), 4);
+    }
+
+    #[test]
+    fn fast_forwards() {
+        for n in [0, 1, 2, 10, 57, 1000] {
+            assert_eq!(nth_state(0, small, n), naive(0, small, n));
+            assert_eq!(nth_state(3, pseudo_random, n), naive(3, pseudo_random, n));
+        }
+        assert_eq!(nth_state(0, small, 1_000_000_000), 4);
+    }
+
+    #[test]
+    fn records_hits() {
+        let hits = find_hits(0, small, |&x| x == 1 || x == 3);
+        assert_eq!(
+            hits.cycle,
+            Cycle {
+                offset: 2,
+                length: 3
+            }
+        );
+        assert_eq!(hits.transient, vec![1]);
+        assert_eq!(hits.periodic, vec![3]);
+        for n in 0..50 {
+            assert_eq!(
+                hits.contains(n),
+                [1, 3].contains(&naive(0, small, n)),
+                "{n}"
+            );
+        }
+    }
+}
@@ -0,0 +1,454 @@
+/// Directed acyclic graphs with weighted edges.
+///
+/// A [`Dag`] is checked for cycles once when it is built and keeps a topological order, so the
+/// path and reachability queries are single passes over the nodes in that order. Nodes are dense
+/// [`NodeId`]s, like the indices of the adjacency lists that puzzles build along the way.
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::Range;
+
+use num::Zero;
+
+use crate::bits::BitSet;
+use crate::graph::{Graph, NodeId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DagError {
+    /// The edges form a cycle. Holds the nodes that are on a cycle or only reachable through one.
+    Cycle(Vec<NodeId>),
+    /// An edge refers to a node that is not below the number of nodes.
+    UnknownNode { node: NodeId, len: usize },
+}
+
+impl Display for DagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DagError::Cycle(nodes) => write!(f, "the graph has a cycle through {nodes:?}"),
+            DagError::UnknownNode { node, len } => {
+                write!(f, "node {node} is out of bounds for a graph of {len} nodes")
+            }
+        }
+    }
+}
+
+impl Error for DagError {}
+
+#[derive(Debug, Clone)]
+pub struct Dag<W = usize> {
+    successors: Vec<Vec<(NodeId, W)>>,
+    predecessors: Vec<Vec<(NodeId, W)>>,
+    order: Vec<NodeId>,
+}
+
+impl<W: Copy> Dag<W> {
+    /// The graph on the nodes below `len` with the edges `(from, to, weight)`, or an error if they
+    /// form a cycle. Edges are listed in the order they are given.
+    pub fn from_weighted_edges(
+        len: usize,
+        edges: impl IntoIterator<Item = (NodeId, NodeId, W)>,
+    ) -> Result<Self, DagError> {
+        let mut successors = vec![Vec::new(); len];
+        let mut predecessors = vec![Vec::new(); len];
+        for (from, to, w) in edges {
+            if let Some(node) = [from, to].into_iter().find(|&id| id >= len) {
+                return Err(DagError::UnknownNode { node, len });
+            }
+            successors[from].push((to, w));
+            predecessors[to].push((from, w));
+        }
+
+        // Kahn's algorithm, where the nodes that never run out of predecessors are on a cycle.
+        let mut in_degree = predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut order = (0..len)
+            .filter(|&id| in_degree[id] == 0)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while i < order.len() {
+            for &(next, _) in &successors[order[i]] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+        if order.len() < len {
+            let cycle = (0..len).filter(|&id| in_degree[id] > 0).collect();
+            return Err(DagError::Cycle(cycle));
+        }
+
+        Ok(Dag {
+            successors,
+            predecessors,
+            order,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<NodeId> {
+        0..self.len()
+    }
+
+    /// The nodes in an order where every edge goes forward.
+    pub fn topological_order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    pub fn successors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.successors[id].iter().map(|&(next, _)| next)
+    }
+
+    pub fn predecessors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.predecessors[id].iter().map(|&(prev, _)| prev)
+    }
+
+    /// The edges leaving `id`, as `(to, weight)`.
+    pub fn out_edges(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.successors[id]
+    }
+
+    /// The edges entering `id`, as `(from, weight)`.
+    pub fn in_edges(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.predecessors[id]
+    }
+
+    /// The path weight from `start` to every node, keeping the candidate for which `better` holds.
+    fn paths(&self, start: NodeId, better: impl Fn(W, W) -> bool) -> Vec<Option<W>>
+    where
+        W: Zero,
+    {
+        let mut best = vec![None; self.len()];
+        best[start] = Some(W::zero());
+        for &id in &self.order {
+            let Some(d) = best[id] else { continue };
+            for &(next, w) in &self.successors[id] {
+                let d = d + w;
+                if best[next].is_none_or(|b| better(d, b)) {
+                    best[next] = Some(d);
+                }
+            }
+        }
+        best
+    }
+
+    /// The weight of the heaviest path from `start` to every node, if there is one.
+    pub fn longest_paths(&self, start: NodeId) -> Vec<Option<W>>
+    where
+        W: Zero + Ord,
+    {
+        self.paths(start, |a, b| a > b)
+    }
+
+    /// The weight of the lightest path from `start` to every node, if there is one. Unlike
+    /// Dijkstra, negative weights are fine.
+    pub fn shortest_paths(&self, start: NodeId) -> Vec<Option<W>>
+    where
+        W: Zero + Ord,
+    {
+        self.paths(start, |a, b| a < b)
+    }
+
+    /// The nodes reachable from `start`, including itself.
+    pub fn reachable(&self, start: NodeId) -> BitSet {
+        let mut reached = BitSet::new(self.len());
+        reached.insert(start);
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            stack.extend(self.successors(id).filter(|&next| reached.insert(next)));
+        }
+        reached
+    }
+
+    /// The number of other nodes reachable from every node. This keeps a set of reachable nodes for
+    /// every node, so it takes `len² / 8` bytes.
+    pub fn reachable_counts(&self) -> Vec<usize> {
+        let mut reached = vec![BitSet::new(self.len()); self.len()];
+        for &id in self.order.iter().rev() {
+            let mut set = BitSet::new(self.len());
+            for next in self.successors(id) {
+                set.insert(next);
+                set.union_with(&reached[next]);
+            }
+            reached[id] = set;
+        }
+        reached.iter().map(BitSet::count).collect()
+    }
+
+    /// The dominator tree of the nodes reachable from `root`, where a node dominates another when
+    /// every path from the root to the other goes through it.
+    ///
+    /// In a DAG, the immediate dominator of a node is the deepest common ancestor of its
+    /// predecessors in the tree, and all of them come earlier in topological order. The tree is
+    /// built in that order, with binary lifting for the common ancestors, in `O(edges · log len)`.
+    pub fn dominators(&self, root: NodeId) -> Dominators {
+        let len = self.len();
+        let levels = (usize::BITS - len.leading_zeros()).max(1) as usize;
+        // up[k][id] is the 2^k-th dominator above `id`, or the root above it.
+        let mut up = vec![vec![root; len]; levels];
+        let mut depth = vec![0; len];
+        let mut idom = vec![None; len];
+        let mut reached = BitSet::new(len);
+        reached.insert(root);
+
+        let ancestor = |up: &[Vec<NodeId>], mut id: NodeId, mut steps: usize| {
+            for level in up {
+                if steps == 0 {
+                    break;
+                }
+                if steps & 1 == 1 {
+                    id = level[id];
+                }
+                steps >>= 1;
+            }
+            id
+        };
+        let common = |up: &[Vec<NodeId>], depth: &[usize], a: NodeId, b: NodeId| {
+            let (a, b) = if depth[a] < depth[b] { (b, a) } else { (a, b) };
+            let (mut a, mut b) = (ancestor(up, a, depth[a] - depth[b]), b);
+            if a == b {
+                return a;
+            }
+            for level in up.iter().rev() {
+                if level[a] != level[b] {
+                    (a, b) = (level[a], level[b]);
+                }
+            }
+            up[0][a]
+        };
+
+        for &id in &self.order {
+            if id == root {
+                continue;
+            }
+            let Some(dominator) = self
+                .predecessors(id)
+                .filter(|&prev| reached.contains(prev))
+                .reduce(|a, b| common(&up, &depth, a, b))
+            else {
+                continue;
+            };
+            reached.insert(id);
+            idom[id] = Some(dominator);
+            depth[id] = depth[dominator] + 1;
+            up[0][id] = dominator;
+            for k in 1..levels {
+                up[k][id] = up[k - 1][up[k - 1][id]];
+            }
+        }
+
+        // every node adds itself to the subtrees of its dominators, from the leaves up.
+        let mut sizes = (0..len)
+            .map(|id| usize::from(reached.contains(id)))
+            .collect::<Vec<_>>();
+        for &id in self.order.iter().rev() {
+            if let Some(dominator) = idom[id] {
+                sizes[dominator] += sizes[id];
+            }
+        }
+
+        Dominators { root, idom, sizes }
+    }
+}
+
+/// The dominator tree found by [`Dag::dominators`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dominators {
+    root: NodeId,
+    idom: Vec<Option<NodeId>>,
+    sizes: Vec<usize>,
+}
+
+impl Dominators {
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// The closest node that dominates `id`, i.e. its parent in the tree. `None` for the root and
+    /// for nodes that cannot be reached from it.
+    pub fn immediate_dominator(&self, id: NodeId) -> Option<NodeId> {
+        self.idom[id]
+    }
+
+    pub fn is_reachable(&self, id: NodeId) -> bool {
+        self.sizes[id] > 0
+    }
+
+    /// Whether every path from the root to `b` goes through `a`. Every reachable node dominates
+    /// itself.
+    pub fn dominates(&self, a: NodeId, b: NodeId) -> bool {
+        if !self.is_reachable(b) {
+            return false;
+        }
+        std::iter::successors(Some(b), |&id| self.idom[id]).any(|id| id == a)
+    }
+
+    /// The number of nodes that `id` dominates, including itself, i.e. the ones that the root can
+    /// no longer reach without it.
+    pub fn subtree_size(&self, id: NodeId) -> usize {
+        self.sizes[id]
+    }
+}
+
+impl Dag<usize> {
+    /// The graph where `successors[i]` lists the nodes that `i` has edges to, every edge weighing 1.
+    pub fn from_successors(successors: &[Vec<NodeId>]) -> Result<Self, DagError> {
+        let edges = successors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, next)| next.iter().map(move |&to| (from, to, 1)));
+        Self::from_weighted_edges(successors.len(), edges)
+    }
+
+    /// The graph where `predecessors[i]` lists the nodes that have edges to `i`, every edge weighing
+    /// 1, e.g. what every node depends on.
+    pub fn from_predecessors(predecessors: &[Vec<NodeId>]) -> Result<Self, DagError> {
+        let edges = predecessors
+            .iter()
+            .enumerate()
+            .flat_map(|(to, prev)| prev.iter().map(move |&from| (from, to, 1)));
+        Self::from_weighted_edges(predecessors.len(), edges)
+    }
+}
+
+impl<L> TryFrom<&Graph<L>> for Dag<usize> {
+    type Error = DagError;
+
+    /// The same nodes and edges, every edge weighing 1.
+    fn try_from(graph: &Graph<L>) -> Result<Self, DagError> {
+        let edges = graph
+            .nodes()
+            .flat_map(|from| graph.successors(from).iter().map(move |&to| (from, to, 1)));
+        Self::from_weighted_edges(graph.len(), edges)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Dag, DagError};
+    use crate::graph::Graph;
+
+    /// 0 → 1 → 3 and 0 → 2 → 3 with different weights, and 4 → 3 on its own.
+    fn diamond() -> Dag<i32> {
+        Dag::from_weighted_edges(5, [(0, 1, 2), (0, 2, 5), (1, 3, 1), (2, 3, -4), (4, 3, 1)])
+            .unwrap()
+    }
+
+    #[test]
+    fn orders_topologically() {
+        let dag = diamond();
+        let order = dag.topological_order();
+        let position = |id| order.iter().position(|&x| x == id).unwrap();
+        for id in dag.nodes() {
+            for next in dag.successors(id) {
+                assert!(position(id) < position(next));
+            }
+        }
+        assert_eq!(dag.predecessors(3).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(dag.out_edges(0), &[(1, 2), (2, 5)]);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let cycle = Dag::from_successors(&[vec![1], vec![2], vec![1, 3], vec![]]);
+        assert_eq!(cycle.unwrap_err(), DagError::Cycle(vec![1, 2, 3]));
+        let unknown = Dag::from_predecessors(&[vec![], vec![2]]);
+        assert_eq!(
+            unknown.unwrap_err(),
+            DagError::UnknownNode { node: 2, len: 2 }
+        );
+    }
+
+    #[test]
+    fn finds_longest_and_shortest_paths() {
+        let dag = diamond();
+        assert_eq!(
+            dag.longest_paths(0),
+            vec![Some(0), Some(2), Some(5), Some(3), None]
+        );
+        // negative weights are fine without cycles.
+        assert_eq!(dag.shortest_paths(0)[3], Some(1));
+        assert_eq!(
+            dag.shortest_paths(4),
+            vec![None, None, None, Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn counts_reachable_nodes() {
+        let dag = diamond();
+        assert_eq!(dag.reachable_counts(), vec![3, 1, 1, 0, 1]);
+        assert_eq!(dag.reachable(2).iter().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn builds_dominator_trees() {
+        // 0 → 1 → 3 → 4 and 0 → 2 → 3, with 1 → 5 and 6 → 5 from outside.
+        let dag = Dag::from_successors(&[
+            vec![1, 2],
+            vec![3, 5],
+            vec![3],
+            vec![4],
+            vec![],
+            vec![],
+            vec![5],
+        ])
+        .unwrap();
+        let tree = dag.dominators(0);
+        let idoms = dag
+            .nodes()
+            .map(|id| tree.immediate_dominator(id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            idoms,
+            vec![None, Some(0), Some(0), Some(0), Some(3), Some(1), None]
+        );
+        let sizes = dag
+            .nodes()
+            .map(|id| tree.subtree_size(id))
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![6, 2, 1, 2, 1, 1, 0]);
+
+        assert!(tree.dominates(3, 4) && tree.dominates(0, 4) && tree.dominates(4, 4));
+        assert!(!tree.dominates(1, 3) && !tree.dominates(0, 6));
+        assert!(!tree.is_reachable(6));
+    }
+
+    #[test]
+    fn dominates_along_long_chains() {
+        // a ladder where every rung joins two rails, so only the rungs dominate what follows.
+        let n = 1000;
+        let mut successors = vec![Vec::new(); 3 * n + 1];
+        for k in 0..n {
+            let (rung, left, right) = (3 * k, 3 * k + 1, 3 * k + 2);
+            successors[rung] = vec![left, right];
+            successors[left] = vec![rung + 3];
+            successors[right] = vec![rung + 3];
+        }
+        let dag = Dag::from_successors(&successors).unwrap();
+        let tree = dag.dominators(0);
+        assert_eq!(tree.immediate_dominator(3 * n), Some(3 * (n - 1)));
+        assert_eq!(tree.subtree_size(3), 3 * n - 2);
+        assert_eq!(tree.subtree_size(4), 1);
+        assert!(tree.dominates(6, 3 * n) && !tree.dominates(7, 3 * n));
+    }
+
+    #[test]
+    fn converts_graphs() {
+        let graph: Graph<&str> = [("a", "b"), ("b", "c"), ("a", "c")].into_iter().collect();
+        let dag = Dag::try_from(&graph).unwrap();
+        assert_eq!(dag.topological_order(), &[0, 1, 2]);
+        assert_eq!(dag.longest_paths(0)[2], Some(2));
+        assert_eq!(dag.shortest_paths(0)[2], Some(1));
+
+        let mut cyclic = graph;
+        cyclic.add_labelled_edge("c", "a");
+        assert!(Dag::try_from(&cyclic).is_err());
+    }
+}
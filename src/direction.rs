@@ -0,0 +1,229 @@
+/// Compass directions for moving around grids.
+///
+/// Deltas are `(di, dj)` pairs in grid coordinates: rows grow downwards, so north is `(-1, 0)`.
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// One of the eight compass directions. The cardinal ones are also used for 4-way movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// The four cardinal directions, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    /// All eight directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 8]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The `(di, dj)` offset of one step in this direction.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::N => (-1, 0),
+            Direction::NE => (-1, 1),
+            Direction::E => (0, 1),
+            Direction::SE => (1, 1),
+            Direction::S => (1, 0),
+            Direction::SW => (1, -1),
+            Direction::W => (0, -1),
+            Direction::NW => (-1, -1),
+        }
+    }
+
+    /// Moves a signed position `n` steps in this direction.
+    pub fn advance(self, (i, j): (isize, isize), n: isize) -> (isize, isize) {
+        let (di, dj) = self.delta();
+        (i + di * n, j + dj * n)
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::from_index(self.index() + 4)
+    }
+
+    /// Turns right by 90 degrees.
+    pub fn clockwise(self) -> Self {
+        Self::from_index(self.index() + 2)
+    }
+
+    /// Turns left by 90 degrees.
+    pub fn counter_clockwise(self) -> Self {
+        Self::from_index(self.index() + 6)
+    }
+
+    /// Turns right by 45 degrees.
+    pub fn clockwise_45(self) -> Self {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// Turns left by 45 degrees.
+    pub fn counter_clockwise_45(self) -> Self {
+        Self::from_index(self.index() + 7)
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        self.index().is_multiple_of(2)
+    }
+
+    /// The position of a cardinal direction in [`Direction::CARDINAL`], e.g. to index per-direction
+    /// tables, or `None` for a diagonal one.
+    pub fn cardinal_index(self) -> Option<usize> {
+        match self {
+            Direction::N => Some(0),
+            Direction::E => Some(1),
+            Direction::S => Some(2),
+            Direction::W => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Whether this is north or south.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::N | Direction::S)
+    }
+
+    /// Whether this is east or west.
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::E | Direction::W)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirectionError(String);
+
+impl Display for ParseDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+
+impl Error for ParseDirectionError {}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    /// Accepts `U/R/D/L`, `N/E/S/W` and arrows, e.g. `^>v<` or `↑→↓←↗↘↙↖`.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' | '↑' => Ok(Direction::N),
+            'R' | 'E' | '>' | '→' => Ok(Direction::E),
+            'D' | 'S' | 'v' | '↓' => Ok(Direction::S),
+            'L' | 'W' | '<' | '←' => Ok(Direction::W),
+            '↗' => Ok(Direction::NE),
+            '↘' => Ok(Direction::SE),
+            '↙' => Ok(Direction::SW),
+            '↖' => Ok(Direction::NW),
+            c => Err(ParseDirectionError(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    /// Accepts everything [`Direction::try_from`] does, and the diagonals `NE`, `SE`, `SW` and `NW`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, _) => Direction::try_from(c),
+            (Some('N'), Some('E'), None) => Ok(Direction::NE),
+            (Some('S'), Some('E'), None) => Ok(Direction::SE),
+            (Some('S'), Some('W'), None) => Ok(Direction::SW),
+            (Some('N'), Some('W'), None) => Ok(Direction::NW),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Direction;
+
+    #[test]
+    fn turns_and_reverses() {
+        assert_eq!(Direction::N.clockwise(), Direction::E);
+        assert_eq!(Direction::W.clockwise(), Direction::N);
+        assert_eq!(Direction::N.counter_clockwise(), Direction::W);
+        assert_eq!(Direction::SE.opposite(), Direction::NW);
+        assert_eq!(Direction::NW.clockwise_45(), Direction::N);
+        assert_eq!(Direction::N.counter_clockwise_45(), Direction::NW);
+
+        for d in Direction::ALL {
+            assert_eq!(d.opposite().opposite(), d);
+            assert_eq!(d.clockwise().counter_clockwise(), d);
+            let (di, dj) = d.delta();
+            assert_eq!(d.opposite().delta(), (-di, -dj));
+        }
+    }
+
+    #[test]
+    fn classifies() {
+        assert!(Direction::CARDINAL.iter().all(|d| d.is_cardinal()));
+        assert!(!Direction::NE.is_cardinal());
+        assert!(Direction::S.is_vertical() && !Direction::S.is_horizontal());
+        assert!(Direction::W.is_horizontal());
+    }
+
+    #[test]
+    fn indexes_cardinals() {
+        for (i, d) in Direction::CARDINAL.into_iter().enumerate() {
+            assert_eq!(d.cardinal_index(), Some(i));
+        }
+        assert_eq!(Direction::SW.cardinal_index(), None);
+    }
+
+    #[test]
+    fn advances() {
+        assert_eq!(Direction::N.advance((0, 0), 3), (-3, 0));
+        assert_eq!(Direction::SW.advance((1, 1), 2), (3, -1));
+    }
+
+    #[test]
+    fn parses() {
+        for (s, d) in [
+            ("U", Direction::N),
+            ("R", Direction::E),
+            ("v", Direction::S),
+            ("←", Direction::W),
+            ("S", Direction::S),
+            ("NE", Direction::NE),
+            ("↙", Direction::SW),
+        ] {
+            assert_eq!(s.parse::<Direction>(), Ok(d));
+        }
+        assert!("X".parse::<Direction>().is_err());
+        assert!("NNE".parse::<Direction>().is_err());
+        assert!(Direction::try_from('x').is_err());
+    }
+}
@@ -0,0 +1,209 @@
+/// Flood fill and connected components of grid cells.
+///
+/// Cells are connected to their orthogonal neighbours when both are passable. The fills use an
+/// explicit stack instead of recursion, so they work on grids of any size.
+///
+/// Some puzzles let you squeeze between cells, e.g. between two parallel pipes. For those, every
+/// cell is split into `scale × scale` sub-cells that are passable or not on their own, and the fill
+/// runs on the finer lattice. Each cell then belongs to the component of its central sub-cell.
+use crate::bits::BitGrid;
+use crate::grid::{Grid, Pos};
+
+/// The positions reachable from `starts` through passable cells. Starts that are not passable are
+/// ignored.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    starts: impl IntoIterator<Item = Pos>,
+    mut passable: impl FnMut(Pos, &T) -> bool,
+) -> BitGrid {
+    let mut filled = BitGrid::new(grid.size());
+    let mut stack = Vec::new();
+    for start in starts {
+        if passable(start, &grid[start]) && filled.insert(start) {
+            stack.push(start);
+        }
+    }
+    while let Some(pos) = stack.pop() {
+        for next in grid.neighbours_4(pos) {
+            if !filled.contains(next) && passable(next, &grid[next]) {
+                filled.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+    filled
+}
+
+/// The connected components of the passable cells of a grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    labels: Grid<Option<usize>>,
+    sizes: Vec<usize>,
+    on_border: Vec<bool>,
+}
+
+impl Components {
+    /// The component of the cell at `pos`, or `None` if it is not passable. Components are numbered
+    /// from 0 in the row-major order of their first cell.
+    pub fn label(&self, pos: Pos) -> Option<usize> {
+        self.labels[pos]
+    }
+
+    pub fn labels(&self) -> &Grid<Option<usize>> {
+        &self.labels
+    }
+
+    /// The number of cells in every component.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Whether the component reaches the edge of the grid, i.e. it is not enclosed. With sub-cells,
+    /// this includes reaching the edge between cells.
+    pub fn touches_border(&self, label: usize) -> bool {
+        self.on_border[label]
+    }
+
+    /// The labels of the components that do not reach the edge of the grid.
+    pub fn enclosed(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&l| !self.on_border[l])
+    }
+}
+
+/// The components of the cells for which `passable` holds.
+pub fn components<T>(grid: &Grid<T>, mut passable: impl FnMut(Pos, &T) -> bool) -> Components {
+    components_fine(grid, 1, |pos, cell, _| passable(pos, cell))
+}
+
+/// The components when every cell is split into `scale × scale` sub-cells, where
+/// `passable(pos, cell, (di, dj))` tells whether sub-cell `(di, dj)` of the cell at `pos` can be
+/// crossed. A cell belongs to the component of its central sub-cell, so `scale` should be odd.
+pub fn components_fine<T>(
+    grid: &Grid<T>,
+    scale: usize,
+    mut passable: impl FnMut(Pos, &T, Pos) -> bool,
+) -> Components {
+    assert!(scale > 0, "the scale must be positive");
+    let (h, w) = grid.size();
+    let fine = Grid::from_fn((h * scale, w * scale), |(i, j)| {
+        let pos = (i / scale, j / scale);
+        passable(pos, &grid[pos], (i % scale, j % scale))
+    });
+
+    // label the fine lattice, one component at a time.
+    let mut fine_labels = Grid::from_elem(fine.size(), None);
+    let mut fine_on_border = Vec::new();
+    let mut stack = Vec::new();
+    for start in fine.positions() {
+        if !fine[start] || fine_labels[start].is_some() {
+            continue;
+        }
+        let label = fine_on_border.len();
+        let mut on_border = false;
+        fine_labels[start] = Some(label);
+        stack.push(start);
+        while let Some(pos) = stack.pop() {
+            on_border |= fine.neighbours_4(pos).count() < 4;
+            for next in fine.neighbours_4(pos) {
+                if fine[next] && fine_labels[next].is_none() {
+                    fine_labels[next] = Some(label);
+                    stack.push(next);
+                }
+            }
+        }
+        fine_on_border.push(on_border);
+    }
+
+    // keep the components that contain cells, renumbered in the order of their first cell.
+    let center = scale / 2;
+    let mut renumber = vec![None; fine_on_border.len()];
+    let mut sizes = Vec::new();
+    let mut on_border = Vec::new();
+    let labels = Grid::from_fn((h, w), |(i, j)| {
+        let fine_label = fine_labels[(i * scale + center, j * scale + center)]?;
+        let label = *renumber[fine_label].get_or_insert_with(|| {
+            sizes.push(0);
+            on_border.push(fine_on_border[fine_label]);
+            sizes.len() - 1
+        });
+        sizes[label] += 1;
+        Some(label)
+    });
+
+    Components {
+        labels,
+        sizes,
+        on_border,
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{components, components_fine, flood_fill};
+    use crate::grid::Grid;
+
+    fn walls(input: &str) -> Grid<bool> {
+        Grid::parse(input, |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn fills_from_starts() {
+        let grid = walls(".#.\n.#.\n...\n##.");
+        let filled = flood_fill(&grid, [(0, 0)], |_, &wall| !wall);
+        assert_eq!(filled.count(), 8);
+        assert!(!filled.contains((3, 0)));
+
+        // walls are never filled, even from a start.
+        assert!(flood_fill(&grid, [(0, 1)], |_, &wall| !wall).is_empty());
+    }
+
+    #[test]
+    fn labels_components() {
+        let grid = walls("..#..\n###.#\n.#.#.\n.###.");
+        let c = components(&grid, |_, &wall| !wall);
+        assert_eq!(c.sizes(), &[2, 3, 2, 1, 2]);
+        assert_eq!(c.label((0, 3)), Some(1));
+        assert_eq!(c.label((1, 3)), Some(1));
+        assert_eq!(c.label((2, 2)), Some(3));
+        assert_eq!(c.label((0, 2)), None);
+        assert_eq!(c.enclosed().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn squeezes_between_cells() {
+        // two walls that only fill the left half of their cells leave a gap to squeeze through.
+        let grid = walls("...\n.#.\n.#.\n...");
+        let half_walls = components_fine(&grid, 3, |_, &wall, (_, dj)| !wall || dj == 2);
+        assert_eq!(half_walls.len(), 1);
+        assert_eq!(half_walls.sizes(), &[10]);
+
+        // a ring of walls with only their centers blocked still encloses nothing at cell level.
+        let grid = walls("###\n#.#\n###");
+        let ring = components_fine(&grid, 3, |_, &wall, sub| !wall || sub != (1, 1));
+        assert_eq!(ring.len(), 1);
+        let solid = components_fine(&grid, 3, |_, &wall, _| !wall);
+        assert_eq!(solid.enclosed().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn handles_large_grids() {
+        // a single snake-shaped path through a big grid, which is deep enough to overflow a
+        // recursive fill.
+        let n = 1000;
+        let grid = Grid::from_fn((n, n), |(i, j)| {
+            i % 2 == 1 && j != if i % 4 == 1 { n - 1 } else { 0 }
+        });
+        let c = components(&grid, |_, &wall| !wall);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c.sizes()[0], n * n / 2 + n / 2);
+    }
+}
@@ -0,0 +1,401 @@
+/// Directed graphs whose nodes are named in the puzzle input.
+///
+/// Labels are interned into dense [`NodeId`]s when nodes are added, so the algorithms work on
+/// indices while the labels stay available for debugging output. Undirected graphs are stored with
+/// an edge in each direction.
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+use std::ops::Range;
+
+/// The index of a node, in the order the nodes were added.
+pub type NodeId = usize;
+
+#[derive(Debug, Clone)]
+pub struct Graph<L> {
+    labels: Vec<L>,
+    ids: HashMap<L, NodeId>,
+    successors: Vec<Vec<NodeId>>,
+    predecessors: Vec<Vec<NodeId>>,
+}
+
+impl<L: Clone + Eq + Hash> Graph<L> {
+    pub fn new() -> Self {
+        Graph {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+        }
+    }
+
+    /// The id of the node with this label, adding the node if it is new.
+    pub fn node(&mut self, label: L) -> NodeId {
+        if let Some(&id) = self.ids.get(&label) {
+            return id;
+        }
+        let id = self.labels.len();
+        self.labels.push(label.clone());
+        self.ids.insert(label, id);
+        self.successors.push(Vec::new());
+        self.predecessors.push(Vec::new());
+        id
+    }
+
+    /// The id of the node with this label, if there is one.
+    pub fn id<Q>(&self, label: &Q) -> Option<NodeId>
+    where
+        L: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: NodeId) -> &L {
+        &self.labels[id]
+    }
+
+    /// Adds an edge from `from` to `to`. Parallel edges are kept, and successors are listed in the
+    /// order their edges were added.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.successors[from].push(to);
+        self.predecessors[to].push(from);
+    }
+
+    /// Adds an edge between two labelled nodes, adding the nodes if needed.
+    pub fn add_labelled_edge(&mut self, from: L, to: L) -> (NodeId, NodeId) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.add_edge(from, to);
+        (from, to)
+    }
+
+    /// Adds an edge in each direction between two labelled nodes.
+    pub fn add_undirected_edge(&mut self, a: L, b: L) -> (NodeId, NodeId) {
+        let (a, b) = self.add_labelled_edge(a, b);
+        self.add_edge(b, a);
+        (a, b)
+    }
+
+    /// The same graph with every edge reversed.
+    pub fn reversed(&self) -> Self {
+        Graph {
+            labels: self.labels.clone(),
+            ids: self.ids.clone(),
+            successors: self.predecessors.clone(),
+            predecessors: self.successors.clone(),
+        }
+    }
+}
+
+impl<L> Graph<L> {
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<NodeId> {
+        0..self.labels.len()
+    }
+
+    pub fn successors(&self, id: NodeId) -> &[NodeId] {
+        &self.successors[id]
+    }
+
+    pub fn predecessors(&self, id: NodeId) -> &[NodeId] {
+        &self.predecessors[id]
+    }
+
+    /// The nodes reachable from `start`, in breadth-first order.
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        seen[start] = true;
+        let mut order = vec![start];
+        let mut i = 0;
+        while i < order.len() {
+            for &next in &self.successors[order[i]] {
+                if !seen[next] {
+                    seen[next] = true;
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+        order
+    }
+
+    /// The number of edges on the shortest path from `start` to every node, if there is one.
+    pub fn distances(&self, start: NodeId) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.len()];
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(id) = queue.pop_front() {
+            let d = distances[id].unwrap();
+            for &next in &self.successors[id] {
+                if distances[next].is_none() {
+                    distances[next] = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// The nodes reachable from `start`, in depth-first preorder.
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            if seen[id] {
+                continue;
+            }
+            seen[id] = true;
+            order.push(id);
+            // push in reverse so that the first successor is visited first.
+            stack.extend(self.successors[id].iter().rev().filter(|&&n| !seen[n]));
+        }
+        order
+    }
+
+    /// The weakly connected components, i.e. ignoring the direction of the edges.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut component = vec![start];
+            let mut i = 0;
+            while i < component.len() {
+                let id = component[i];
+                for &next in self.successors[id].iter().chain(&self.predecessors[id]) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        component.push(next);
+                    }
+                }
+                i += 1;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// An order of the nodes where every edge goes forward, or `None` if the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<NodeId>> {
+        let mut in_degree = self.predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut order = self
+            .nodes()
+            .filter(|&id| in_degree[id] == 0)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while i < order.len() {
+            for &next in &self.successors[order[i]] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Pushes as much flow as possible from `source` to `sink` with Edmonds-Karp, where every edge
+    /// has a capacity of 1. Returns the flow and which nodes can still be reached from the source.
+    fn flow(&self, source: NodeId, sink: NodeId) -> (usize, Vec<bool>) {
+        // residual edges are stored in pairs, so the reverse of edge `e` is `e ^ 1`.
+        let mut to = Vec::new();
+        let mut capacity = Vec::new();
+        let mut edges = vec![Vec::new(); self.len()];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for &next in successors {
+                edges[from].push(to.len());
+                to.push(next);
+                capacity.push(1);
+                edges[next].push(to.len());
+                to.push(from);
+                capacity.push(0);
+            }
+        }
+
+        let mut flow = 0;
+        loop {
+            let mut parent_edge = vec![None; self.len()];
+            let mut seen = vec![false; self.len()];
+            seen[source] = true;
+            let mut queue = VecDeque::from([source]);
+            while let Some(id) = queue.pop_front() {
+                for &e in &edges[id] {
+                    if capacity[e] > 0 && !seen[to[e]] {
+                        seen[to[e]] = true;
+                        parent_edge[to[e]] = Some(e);
+                        queue.push_back(to[e]);
+                    }
+                }
+            }
+            if !seen[sink] {
+                return (flow, seen);
+            }
+
+            let mut path = Vec::new();
+            let mut id = sink;
+            while let Some(e) = parent_edge[id] {
+                path.push(e);
+                id = to[e ^ 1];
+            }
+            let bottleneck = path.iter().map(|&e| capacity[e]).min().unwrap();
+            for e in path {
+                capacity[e] -= bottleneck;
+                capacity[e ^ 1] += bottleneck;
+            }
+            flow += bottleneck;
+        }
+    }
+
+    /// The maximum flow from `source` to `sink`, where every edge has a capacity of 1.
+    pub fn max_flow(&self, source: NodeId, sink: NodeId) -> usize {
+        self.flow(source, sink).0
+    }
+
+    /// The fewest edges to remove to disconnect `sink` from `source`, and the nodes that are still
+    /// reachable from `source` after removing them.
+    pub fn min_cut(&self, source: NodeId, sink: NodeId) -> (usize, Vec<NodeId>) {
+        let (flow, reachable) = self.flow(source, sink);
+        (flow, self.nodes().filter(|&id| reachable[id]).collect())
+    }
+
+    /// The graph in the DOT language of Graphviz, e.g. to render it with `dot -Tsvg`.
+    pub fn to_dot(&self) -> String
+    where
+        L: Display,
+    {
+        let mut dot = String::from("digraph {\n");
+        for (id, label) in self.labels.iter().enumerate() {
+            let label = label.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(dot, "    {id} [label=\"{label}\"];").unwrap();
+        }
+        for (from, successors) in self.successors.iter().enumerate() {
+            for to in successors {
+                writeln!(dot, "    {from} -> {to};").unwrap();
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+impl<L: Clone + Eq + Hash> Default for Graph<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Clone + Eq + Hash> FromIterator<(L, L)> for Graph<L> {
+    /// Builds a graph from directed edges between labelled nodes.
+    fn from_iter<I: IntoIterator<Item = (L, L)>>(iter: I) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in iter {
+            graph.add_labelled_edge(from, to);
+        }
+        graph
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Graph;
+
+    fn diamond() -> Graph<&'static str> {
+        [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("e", "d")]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn interns_labels() {
+        let mut g = diamond();
+        assert_eq!(g.len(), 5);
+        assert_eq!(g.id("c"), Some(2));
+        assert_eq!(*g.label(3), "d");
+        assert_eq!(g.id("x"), None);
+        assert_eq!(g.node("c"), 2);
+        assert_eq!(g.node("x"), 5);
+        assert_eq!(g.successors(0), &[1, 2]);
+        assert_eq!(g.predecessors(3), &[1, 2, 4]);
+    }
+
+    #[test]
+    fn traverses() {
+        let g = diamond();
+        assert_eq!(g.bfs(0), vec![0, 1, 2, 3]);
+        assert_eq!(g.dfs(0), vec![0, 1, 3, 2]);
+        assert_eq!(g.reversed().bfs(3), vec![3, 1, 2, 4, 0]);
+        assert_eq!(
+            g.distances(0),
+            vec![Some(0), Some(1), Some(1), Some(2), None]
+        );
+    }
+
+    #[test]
+    fn finds_components_and_order() {
+        let mut g = diamond();
+        g.add_labelled_edge("x", "y");
+        let components = g.components();
+        assert_eq!(components, vec![vec![0, 1, 2, 3, 4], vec![5, 6]]);
+
+        let order = g.topological_order().unwrap();
+        let position = |id| order.iter().position(|&x| x == id).unwrap();
+        for id in g.nodes() {
+            for &next in g.successors(id) {
+                assert!(position(id) < position(next));
+            }
+        }
+
+        g.add_labelled_edge("d", "a");
+        assert_eq!(g.topological_order(), None);
+    }
+
+    #[test]
+    fn cuts_and_flows() {
+        // two triangles joined by two edges.
+        let mut g = Graph::new();
+        for (a, b) in [
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("x", "y"),
+            ("y", "z"),
+            ("z", "x"),
+            ("a", "x"),
+            ("b", "y"),
+        ] {
+            g.add_undirected_edge(a, b);
+        }
+        let (a, z) = (g.id("a").unwrap(), g.id("z").unwrap());
+        assert_eq!(g.max_flow(a, z), 2);
+        let (cut, side) = g.min_cut(a, z);
+        assert_eq!(cut, 2);
+        let mut labels = side.iter().map(|&id| *g.label(id)).collect::<Vec<_>>();
+        labels.sort();
+        assert_eq!(labels, vec!["a", "b", "c"]);
+
+        assert_eq!(g.max_flow(a, g.id("b").unwrap()), 3);
+    }
+
+    #[test]
+    fn exports_dot() {
+        let g: Graph<&str> = [("a", "b\"")].into_iter().collect();
+        assert_eq!(
+            g.to_dot(),
+            "digraph {\n    0 [label=\"a\"];\n    1 [label=\"b\\\"\"];\n    0 -> 1;\n}"
+        );
+    }
+}
@@ -0,0 +1,547 @@
+/// Two-dimensional grids of cells, as found in most puzzle inputs.
+///
+/// Positions are `(row, column)` pairs, matching the indexing of [`ndarray::Array2`], with the
+/// first row at the top of the input.
+use std::error::Error;
+use std::fmt::{Display, Write};
+use std::ops::{Deref, DerefMut};
+
+use ndarray::{Array2, ArrayView2, ArrayViewMut2, Axis};
+
+use crate::direction::Direction;
+
+/// A position in a grid, as `(row, column)`.
+pub type Pos = (usize, usize);
+
+/// A rectangular grid of cells, backed by an [`Array2`].
+///
+/// The grid dereferences to the underlying array, so all of its methods (`nrows`, `rows`,
+/// `indexed_iter`, indexing with a [`Pos`], ...) are available as well.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input has no lines.
+    Empty,
+    /// A line is not as wide as the first one. Lines are counted from 1.
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character could not be mapped to a cell. Lines and columns are counted from 1.
+    UnexpectedChar {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the grid is empty"),
+            ParseError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} is {found} characters wide, expected {expected}"
+            ),
+            ParseError::UnexpectedChar { line, column, char } => {
+                write!(f, "unexpected character {char:?} at {line}:{column}")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// One of the eight ways to rotate or flip a grid, used to look at it from another side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise: the first row becomes the last column.
+    RotateCw,
+    Rotate180,
+    /// A quarter turn counter-clockwise: the first row becomes the first column, bottom to top.
+    RotateCcw,
+    /// Mirrors left and right.
+    FlipH,
+    /// Mirrors top and bottom.
+    FlipV,
+    /// Swaps rows and columns.
+    Transpose,
+    /// Swaps rows and columns along the other diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::RotateCw,
+        Transform::Rotate180,
+        Transform::RotateCcw,
+        Transform::FlipH,
+        Transform::FlipV,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// The transform whose rows run in `direction`, i.e. where moving right along a row of the
+    /// view is moving in `direction` in the grid. Lines running north are the columns read from
+    /// the bottom, and so on.
+    ///
+    /// This lets an algorithm for one direction work in all four, e.g. tilting everything to the
+    /// end of the rows.
+    pub fn rows_toward(direction: Direction) -> Self {
+        match direction {
+            Direction::E => Transform::Identity,
+            Direction::W => Transform::FlipH,
+            Direction::S => Transform::Transpose,
+            Direction::N => Transform::RotateCw,
+            _ => panic!("rows only run in cardinal directions, not {direction}"),
+        }
+    }
+
+    /// Whether the transform swaps the height and the width.
+    fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::RotateCw
+                | Transform::RotateCcw
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// The axes of the transposed view to invert, as `(rows, columns)`.
+    fn inverted_axes(self) -> (bool, bool) {
+        match self {
+            Transform::Identity | Transform::Transpose => (false, false),
+            Transform::RotateCw | Transform::FlipH => (false, true),
+            Transform::RotateCcw | Transform::FlipV => (true, false),
+            Transform::Rotate180 | Transform::AntiTranspose => (true, true),
+        }
+    }
+}
+
+macro_rules! transform_view {
+    ($view:expr, $transform:expr) => {{
+        let mut view = $view;
+        if $transform.swaps_axes() {
+            view = view.reversed_axes();
+        }
+        let (rows, columns) = $transform.inverted_axes();
+        if rows {
+            view.invert_axis(Axis(0));
+        }
+        if columns {
+            view.invert_axis(Axis(1));
+        }
+        view
+    }};
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid with one line per row, mapping every character to a cell.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        Self::parse_indexed(input, |_, c| cell(c))
+    }
+
+    /// Like [`Grid::parse`], but also passes the position of each character to the mapping, e.g.
+    /// to remember where a start marker was found.
+    pub fn parse_indexed(
+        input: &str,
+        mut cell: impl FnMut(Pos, char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (i, line) in input.lines().enumerate() {
+            let start = cells.len();
+            for (j, c) in line.chars().enumerate() {
+                let value = cell((i, j), c).ok_or(ParseError::UnexpectedChar {
+                    line: i + 1,
+                    column: j + 1,
+                    char: c,
+                })?;
+                cells.push(value);
+            }
+
+            let found = cells.len() - start;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(ParseError::Ragged {
+                        line: i + 1,
+                        expected,
+                        found,
+                    })
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        let width = width.ok_or(ParseError::Empty)?;
+        Ok(Self {
+            cells: Array2::from_shape_vec((height, width), cells).unwrap(),
+        })
+    }
+
+    /// Creates a grid of the given `(height, width)` with all cells set to `value`.
+    pub fn from_elem(shape: (usize, usize), value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: Array2::from_elem(shape, value),
+        }
+    }
+
+    /// Creates a grid of the given `(height, width)` from a function of the position.
+    pub fn from_fn(shape: (usize, usize), f: impl FnMut(Pos) -> T) -> Self {
+        Self {
+            cells: Array2::from_shape_fn(shape, f),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    /// The `(height, width)` of the grid.
+    pub fn size(&self) -> (usize, usize) {
+        self.cells.dim()
+    }
+
+    pub fn into_array(self) -> Array2<T> {
+        self.cells
+    }
+
+    /// Whether a signed position lies inside the grid.
+    pub fn contains(&self, (i, j): (isize, isize)) -> bool {
+        i >= 0 && j >= 0 && (i as usize) < self.height() && (j as usize) < self.width()
+    }
+
+    /// The cell at a signed position, or `None` if it lies outside the grid.
+    pub fn get_signed(&self, (i, j): (isize, isize)) -> Option<&T> {
+        self.contains((i, j))
+            .then(|| &self.cells[(i as usize, j as usize)])
+    }
+
+    /// Moves `pos` by `(di, dj)`, or returns `None` if that leaves the grid.
+    pub fn offset(&self, (i, j): Pos, (di, dj): (isize, isize)) -> Option<Pos> {
+        let target = (i as isize + di, j as isize + dj);
+        self.contains(target)
+            .then_some((target.0 as usize, target.1 as usize))
+    }
+
+    /// Moves `pos` by `(di, dj)`, re-entering on the opposite side when leaving the grid.
+    pub fn offset_wrapping(&self, (i, j): Pos, (di, dj): (isize, isize)) -> Pos {
+        let (h, w) = (self.height() as isize, self.width() as isize);
+        (
+            (i as isize + di).rem_euclid(h) as usize,
+            (j as isize + dj).rem_euclid(w) as usize,
+        )
+    }
+
+    /// Moves `pos` one step in `direction`, or returns `None` if that leaves the grid.
+    pub fn step(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        self.offset(pos, direction.delta())
+    }
+
+    /// Moves `pos` one step in `direction`, re-entering on the opposite side when leaving the grid.
+    pub fn step_wrapping(&self, pos: Pos, direction: Direction) -> Pos {
+        self.offset_wrapping(pos, direction.delta())
+    }
+
+    /// The orthogonal neighbours of `pos` inside the grid, clockwise from north.
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// The surrounding neighbours of `pos` inside the grid, including diagonals, clockwise from
+    /// north.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let (h, w) = self.size();
+        (0..h).flat_map(move |i| (0..w).map(move |j| (i, j)))
+    }
+
+    /// The position of the first cell, in row-major order, that matches `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.cells
+            .indexed_iter()
+            .find_map(|(pos, v)| predicate(v).then_some(pos))
+    }
+
+    /// The cells of row `i`, from left to right.
+    pub fn row_iter(&self, i: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.cells.row(i).into_iter()
+    }
+
+    /// The cells of column `j`, from top to bottom.
+    pub fn column_iter(&self, j: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.cells.column(j).into_iter()
+    }
+
+    /// A view of the grid after `transform`, without copying it.
+    pub fn view(&self, transform: Transform) -> ArrayView2<'_, T> {
+        transform_view!(self.cells.view(), transform)
+    }
+
+    /// A mutable view of the grid after `transform`, where changes go to the original cells.
+    pub fn view_mut(&mut self, transform: Transform) -> ArrayViewMut2<'_, T> {
+        transform_view!(self.cells.view_mut(), transform)
+    }
+
+    /// A copy of the grid after `transform`.
+    pub fn transformed(&self, transform: Transform) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.view(transform).to_owned().into()
+    }
+
+    /// The lines of positions running in `direction`, which may be diagonal. Every line starts at
+    /// the edge of the grid it comes from and continues until it leaves the grid.
+    pub fn lines(
+        &self,
+        direction: Direction,
+    ) -> impl Iterator<Item = impl Iterator<Item = Pos> + '_> + '_ {
+        let back = direction.opposite();
+        self.positions()
+            .filter(move |&pos| self.step(pos, back).is_none())
+            .map(move |start| {
+                std::iter::successors(Some(start), move |&pos| self.step(pos, direction))
+            })
+    }
+
+    /// Creates a grid of the same size by mapping every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.map(f),
+        }
+    }
+
+    /// Renders the grid with one character per cell.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut s = String::with_capacity(self.height() * (self.width() + 1));
+        for row in self.cells.rows() {
+            s.extend(row.iter().map(&mut f));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> From<Array2<T>> for Grid<T> {
+    fn from(cells: Array2<T>) -> Self {
+        Self { cells }
+    }
+}
+
+impl<T> Deref for Grid<T> {
+    type Target = Array2<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T> DerefMut for Grid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cells
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, ParseError, Transform};
+    use crate::direction::Direction;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn parses_and_prints() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!(grid.size(), (2, 3));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            grid.render(|v| if v % 2 == 0 { '#' } else { '.' }),
+            ".#.\n#.#\n"
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(digits(""), Err(ParseError::Empty));
+        assert_eq!(
+            digits("12\n345"),
+            Err(ParseError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            digits("12\n3x"),
+            Err(ParseError::UnexpectedChar {
+                line: 2,
+                column: 2,
+                char: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn parses_with_positions() {
+        let mut start = None;
+        let grid = Grid::parse_indexed("..\n.S", |pos, c| match c {
+            'S' => {
+                start = Some(pos);
+                Some('.')
+            }
+            '.' => Some('.'),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(start, Some((1, 1)));
+        assert_eq!(grid.position(|c| *c != '.'), None);
+    }
+
+    #[test]
+    fn moves_checked_and_wrapping() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.offset((0, 0), (1, 2)), Some((1, 2)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((1, 2), (0, 1)), None);
+        assert_eq!(grid.offset_wrapping((0, 0), (-1, -1)), (1, 2));
+        assert_eq!(grid.offset_wrapping((1, 2), (3, 4)), (0, 0));
+        assert_eq!(grid.step((0, 1), Direction::S), Some((1, 1)));
+        assert_eq!(grid.step((0, 1), Direction::N), None);
+        assert_eq!(grid.step_wrapping((0, 1), Direction::NW), (1, 0));
+        assert_eq!(grid.get_signed((-1, 0)), None);
+        assert_eq!(grid.get_signed((1, 1)), Some(&5));
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        let grid = digits("123\n456\n789").unwrap();
+        let values = |ps: Vec<_>| ps.into_iter().map(|p| grid[p]).collect::<Vec<_>>();
+
+        assert_eq!(
+            values(grid.neighbours_4((1, 1)).collect()),
+            vec![2, 6, 8, 4]
+        );
+        assert_eq!(values(grid.neighbours_4((0, 0)).collect()), vec![2, 4]);
+        assert_eq!(
+            values(grid.neighbours_8((1, 1)).collect()),
+            vec![2, 3, 6, 9, 8, 7, 4, 1]
+        );
+        assert_eq!(values(grid.neighbours_8((2, 2)).collect()), vec![6, 8, 5]);
+    }
+
+    #[test]
+    fn iterates_rows_and_columns() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.row_iter(1).copied().collect::<Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(
+            grid.column_iter(2).rev().copied().collect::<Vec<_>>(),
+            vec![6, 3]
+        );
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+        assert_eq!(grid.map(|v| v * 2)[(0, 2)], 6);
+    }
+
+    #[test]
+    fn transforms_views() {
+        let grid = digits("123\n456").unwrap();
+        let rows = |t| {
+            grid.transformed(t)
+                .rows()
+                .into_iter()
+                .map(|r| r.iter().map(|v| v.to_string()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rows(Transform::Identity), vec!["123", "456"]);
+        assert_eq!(rows(Transform::RotateCw), vec!["41", "52", "63"]);
+        assert_eq!(rows(Transform::Rotate180), vec!["654", "321"]);
+        assert_eq!(rows(Transform::RotateCcw), vec!["36", "25", "14"]);
+        assert_eq!(rows(Transform::FlipH), vec!["321", "654"]);
+        assert_eq!(rows(Transform::FlipV), vec!["456", "123"]);
+        assert_eq!(rows(Transform::Transpose), vec!["14", "25", "36"]);
+        assert_eq!(rows(Transform::AntiTranspose), vec!["63", "52", "41"]);
+
+        // the rows of the view are the lines in that direction.
+        for d in Direction::CARDINAL {
+            let view = grid.view(Transform::rows_toward(d));
+            let lines = grid
+                .lines(d)
+                .map(|line| line.map(|p| grid[p]).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                view.rows()
+                    .into_iter()
+                    .map(|r| r.to_vec())
+                    .collect::<Vec<_>>(),
+                lines
+            );
+        }
+    }
+
+    #[test]
+    fn writes_through_views() {
+        let mut grid = digits("123\n456").unwrap();
+        grid.view_mut(Transform::RotateCw)[(0, 0)] = 0;
+        assert_eq!(grid[(1, 0)], 0);
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = digits("123\n456").unwrap();
+        let lines = |d| {
+            grid.lines(d)
+                .map(|line| line.map(|p| grid[p].to_string()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(Direction::E), vec!["123", "456"]);
+        assert_eq!(lines(Direction::N), vec!["41", "52", "63"]);
+        assert_eq!(lines(Direction::SE), vec!["15", "26", "3", "4"]);
+        assert_eq!(lines(Direction::NW), vec!["3", "4", "51", "62"]);
+    }
+}
@@ -0,0 +1,360 @@
+/// Sets of integer intervals and piecewise mappings between them.
+///
+/// Intervals are half-open [`Range`]s, so `1..4001` holds the 4000 values from 1 to 4000. Puzzles
+/// with inclusive bounds convert them on parsing.
+use std::fmt::Display;
+use std::ops::Range;
+
+use num::PrimInt;
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    /// Adds every value of `range`, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let lo = self.ranges.partition_point(|r| r.end < range.start);
+        let hi = self.ranges.partition_point(|r| r.start <= range.end);
+        let (start, end) = if lo < hi {
+            (
+                self.ranges[lo].start.min(range.start),
+                self.ranges[hi - 1].end.max(range.end),
+            )
+        } else {
+            (range.start, range.end)
+        };
+        self.ranges.splice(lo..hi, [start..end]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for r in &other.ranges {
+            result.insert(r.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // advance whichever interval ends first, it cannot overlap anything else.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The values of `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            let mut start = a.start;
+            // skip the intervals of `other` that end before this one.
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < a.end {
+                let b = &other.ranges[k];
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+            if start < a.end {
+                ranges.push(start..a.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The values that are in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.union(other).difference(&self.intersection(other))
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, r| acc + (r.end - r.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= x);
+        self.ranges.get(i).is_some_and(|r| r.start <= x)
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// The largest value in the set.
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::one())
+    }
+
+    /// The intervals of the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for r in iter {
+            set.insert(r);
+        }
+        set
+    }
+}
+
+impl<T: PrimInt + Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ranges.is_empty() {
+            return write!(f, "∅");
+        }
+        for (i, r) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ∪ ")?;
+            }
+            write!(f, "{}..{}", r.start, r.end)?;
+        }
+        Ok(())
+    }
+}
+
+/// A function on integers that shifts some intervals by an offset and leaves every other value
+/// unchanged, as in "the source range starting at 98 maps to the destination starting at 50".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeMap<T> {
+    /// The source interval and the destination of its first value, sorted by source.
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    /// The identity mapping.
+    pub fn new() -> Self {
+        RangeMap { pieces: Vec::new() }
+    }
+
+    /// Maps `src` to the interval of the same length starting at `dst`.
+    ///
+    /// Panics if `src` overlaps an interval that is already mapped.
+    pub fn insert(&mut self, src: Range<T>, dst: T) {
+        if src.is_empty() {
+            return;
+        }
+        let i = self.pieces.partition_point(|(r, _)| r.end <= src.start);
+        assert!(
+            self.pieces.get(i).is_none_or(|(r, _)| src.end <= r.start),
+            "overlapping source intervals"
+        );
+        self.pieces.insert(i, (src, dst));
+    }
+
+    pub fn get(&self, x: T) -> T {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= x);
+        match self.pieces.get(i) {
+            Some((r, dst)) if r.start <= x => *dst + (x - r.start),
+            _ => x,
+        }
+    }
+
+    /// Splits `range` along the mapped intervals, returning every part with the image of its first
+    /// value.
+    fn segments(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut result = Vec::new();
+        let mut cursor = range.start;
+        let first = self.pieces.partition_point(|(r, _)| r.end <= range.start);
+        for (src, dst) in &self.pieces[first..] {
+            if cursor >= range.end || src.start >= range.end {
+                break;
+            }
+            if cursor < src.start {
+                result.push((cursor..src.start, cursor));
+                cursor = src.start;
+            }
+            let end = src.end.min(range.end);
+            result.push((cursor..end, *dst + (cursor - src.start)));
+            cursor = end;
+        }
+        if cursor < range.end {
+            result.push((cursor..range.end, cursor));
+        }
+        result
+    }
+
+    /// The image of every value in `range`.
+    pub fn map_range(&self, range: Range<T>) -> IntervalSet<T> {
+        self.segments(range)
+            .into_iter()
+            .map(|(r, dst)| dst..dst + (r.end - r.start))
+            .collect()
+    }
+
+    /// The image of every value in `set`.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter().flat_map(|r| self.map_range(r).ranges).collect()
+    }
+
+    /// The mapping that applies `self` and then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut pieces = Vec::new();
+        // values moved by `self` are then looked up in `other`.
+        for (src, dst) in &self.pieces {
+            let image = *dst..*dst + (src.end - src.start);
+            for (r, target) in other.segments(image) {
+                let start = src.start + (r.start - *dst);
+                pieces.push((start..start + (r.end - r.start), target));
+            }
+        }
+        // values left unchanged by `self` are only moved by `other`.
+        let domain: IntervalSet<T> = self.pieces.iter().map(|(r, _)| r.clone()).collect();
+        for (src, dst) in &other.pieces {
+            for r in IntervalSet::from(src.clone()).difference(&domain).iter() {
+                pieces.push((r.clone(), *dst + (r.start - src.start)));
+            }
+        }
+        pieces.retain(|(r, dst)| r.start != *dst);
+        pieces.sort_by_key(|(r, _)| r.start);
+        RangeMap { pieces }
+    }
+
+    /// The mapped intervals with the destination of their first value, sorted by source.
+    pub fn pieces(&self) -> impl Iterator<Item = (Range<T>, T)> + '_ {
+        self.pieces.iter().cloned()
+    }
+}
+
+impl<T: PrimInt> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{IntervalSet, RangeMap};
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(a, b)| a..b).collect()
+    }
+
+    fn values(set: &IntervalSet<i32>) -> Vec<i32> {
+        set.iter().flatten().collect()
+    }
+
+    #[test]
+    fn inserts_and_merges() {
+        let s = set(&[(5, 8), (0, 2), (2, 3), (10, 12), (7, 11)]);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0..3, 5..12]);
+        assert_eq!(s.len(), 10);
+        assert_eq!((s.min(), s.max()), (Some(0), Some(11)));
+        assert!(s.contains(2) && !s.contains(3) && s.contains(11) && !s.contains(12));
+        assert_eq!(s.to_string(), "0..3 ∪ 5..12");
+        assert!(IntervalSet::<i32>::from(4..4).is_empty());
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a = set(&[(0, 5), (8, 12), (20, 25)]);
+        let b = set(&[(3, 9), (11, 21), (30, 31)]);
+        let in_a = |x: &i32| a.contains(*x);
+        let in_b = |x: &i32| b.contains(*x);
+
+        let all = || -5..40;
+        assert_eq!(
+            values(&a.union(&b)),
+            all().filter(|x| in_a(x) || in_b(x)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            values(&a.intersection(&b)),
+            all().filter(|x| in_a(x) && in_b(x)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            values(&a.difference(&b)),
+            all().filter(|x| in_a(x) && !in_b(x)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            values(&a.symmetric_difference(&b)),
+            all().filter(|x| in_a(x) != in_b(x)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn maps_values_and_ranges() {
+        // the seed-to-soil map of 2023 day 5.
+        let mut m = RangeMap::new();
+        m.insert(98..100, 50);
+        m.insert(50..98, 52);
+
+        assert_eq!(m.get(79), 81);
+        assert_eq!(m.get(98), 50);
+        assert_eq!(m.get(10), 10);
+        assert_eq!(m.get(100), 100);
+
+        let image = m.map_range(45..100);
+        assert_eq!(image.iter().collect::<Vec<_>>(), vec![45..100]);
+        let image = m.map_set(&IntervalSet::from(96..102));
+        assert_eq!(image.iter().collect::<Vec<_>>(), vec![50..52, 98..102]);
+    }
+
+    #[test]
+    fn composes() {
+        let mut f = RangeMap::new();
+        f.insert(0..10, 100);
+        f.insert(20..25, 5);
+        let mut g = RangeMap::new();
+        g.insert(3..8, 200);
+        g.insert(102..120, 0);
+
+        let h = f.then(&g);
+        for x in -5..130 {
+            assert_eq!(h.get(x), g.get(f.get(x)), "at {x}");
+        }
+        assert_eq!(h.map_range(0..30), g.map_set(&f.map_range(0..30)));
+    }
+}
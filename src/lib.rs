@@ -1,4 +1,22 @@
 mod day;
+pub mod bits;
+pub mod crt;
+pub mod cycle;
+pub mod dag;
+pub mod direction;
+pub mod flood;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod linear;
+pub mod memo;
+pub mod parsing;
+pub mod point;
+pub mod polygon;
+pub mod scan;
+pub mod search;
+pub mod sequence;
 pub mod template;
+pub mod tiled;
 
 pub use day::*;
@@ -0,0 +1,161 @@
+/// Exact solutions of systems of linear equations.
+///
+/// Puzzle answers are integers, but the systems behind them often have huge coefficients, where
+/// floating-point elimination loses the digits that matter. Here everything is a [`BigRational`],
+/// so the solution is exact and a singular system is detected instead of producing garbage.
+use std::error::Error;
+use std::fmt::Display;
+
+use num::{BigInt, BigRational, One, ToPrimitive, Zero};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearError {
+    /// The matrix is not square, or the right-hand side has a different length.
+    Shape {
+        rows: usize,
+        columns: usize,
+        rhs: usize,
+    },
+    /// The equations do not determine a unique solution.
+    Singular,
+}
+
+impl Display for LinearError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinearError::Shape { rows, columns, rhs } => write!(
+                f,
+                "expected a square system, got {rows}x{columns} with {rhs} right-hand values"
+            ),
+            LinearError::Singular => write!(f, "the system has no unique solution"),
+        }
+    }
+}
+
+impl Error for LinearError {}
+
+/// Solves `a * x = b` by Gaussian elimination over the rationals.
+pub fn solve<T: Clone + Into<BigInt>>(
+    a: &[Vec<T>],
+    b: &[T],
+) -> Result<Vec<BigRational>, LinearError> {
+    let n = a.len();
+    if let Some(columns) = a
+        .iter()
+        .map(Vec::len)
+        .find(|&c| c != n)
+        .or((b.len() != n).then_some(n))
+    {
+        return Err(LinearError::Shape {
+            rows: n,
+            columns,
+            rhs: b.len(),
+        });
+    }
+
+    let ratio = |v: &T| BigRational::from_integer(v.clone().into());
+    // the augmented matrix [a | b].
+    let mut m = a
+        .iter()
+        .zip(b)
+        .map(|(row, v)| row.iter().chain([v]).map(ratio).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|&r| !m[r][col].is_zero())
+            .ok_or(LinearError::Singular)?;
+        m.swap(col, pivot);
+
+        let inverse = BigRational::one() / &m[col][col];
+        for v in m[col][col..].iter_mut() {
+            *v *= &inverse;
+        }
+        let (above, rest) = m.split_at_mut(col);
+        let (pivot_row, below) = rest.split_first_mut().unwrap();
+        for row in above.iter_mut().chain(below) {
+            let factor = row[col].clone();
+            if factor.is_zero() {
+                continue;
+            }
+            for (v, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= &factor * p;
+            }
+        }
+    }
+
+    Ok(m.into_iter().map(|mut row| row.pop().unwrap()).collect())
+}
+
+/// The value as an `i64`, if it is an integer that fits.
+pub fn to_i64(value: &BigRational) -> Option<i64> {
+    if value.is_integer() {
+        value.to_integer().to_i64()
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{solve, to_i64, LinearError};
+    use num::{BigInt, BigRational};
+
+    fn ratio(n: i64, d: i64) -> BigRational {
+        BigRational::new(BigInt::from(n), BigInt::from(d))
+    }
+
+    #[test]
+    fn solves_exactly() {
+        // needs a row swap, as the first pivot is zero.
+        let a = vec![vec![0, 2, 1], vec![1, 1, 1], vec![2, 1, 3]];
+        let x = solve(&a, &[7, 6, 13]).unwrap();
+        assert_eq!(x, vec![ratio(1, 1), ratio(2, 1), ratio(3, 1)]);
+
+        let x = solve(&[vec![3]], &[1]).unwrap();
+        assert_eq!(x, vec![ratio(1, 3)]);
+        assert_eq!(to_i64(&x[0]), None);
+
+        // x + y = 3, x - y = 0
+        let x = solve(&[vec![1, 1], vec![1, -1]], &[3, 0]).unwrap();
+        assert_eq!(x, vec![ratio(3, 2); 2]);
+        assert_eq!(solve::<i32>(&[], &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn keeps_large_values() {
+        // values around 10^15 whose products no float can hold exactly.
+        let big = 1_000_000_000_000_007i64;
+        let a = vec![vec![big, big - 1], vec![big + 1, big]];
+        let b = vec![big * 2 - 1, big * 2 + 1];
+        let x = solve(&a, &b).unwrap();
+        assert_eq!(
+            x.iter().map(to_i64).collect::<Vec<_>>(),
+            vec![Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn detects_bad_systems() {
+        assert_eq!(
+            solve(&[vec![1, 2], vec![2, 4]], &[3, 6]),
+            Err(LinearError::Singular)
+        );
+        assert_eq!(
+            solve(&[vec![1, 2], vec![3]], &[3, 6]),
+            Err(LinearError::Shape {
+                rows: 2,
+                columns: 1,
+                rhs: 2
+            })
+        );
+        assert_eq!(
+            solve(&[vec![1]], &[3, 6]),
+            Err(LinearError::Shape {
+                rows: 1,
+                columns: 1,
+                rhs: 2
+            })
+        );
+    }
+}
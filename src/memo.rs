@@ -0,0 +1,270 @@
+/// Memoization for recursive dynamic programming.
+///
+/// A [`Memo`] is passed down the recursion instead of a hand-rolled map, and
+/// [`Memo::get_or_compute`] either returns the cached value or computes it with the memo at hand
+/// for the recursive calls. Keys that are small indices can use a dense table instead of hashing.
+///
+/// Every memo counts its hits and misses and adds them to a process-wide total when dropped, which
+/// the runner reports in `--time` mode.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Where a [`Memo`] keeps its values.
+pub trait Cache<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+}
+
+/// A cache for any hashable key.
+#[derive(Debug, Clone)]
+pub struct HashCache<K, V>(HashMap<K, V>);
+
+impl<K: Hash + Eq, V> Cache<K, V> for HashCache<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.0.get(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.0.insert(key, value);
+    }
+}
+
+/// A key made of indices below some bounds, which has a place in a dense table.
+pub trait DenseKey: Copy {
+    /// The number of keys below `self`, taken as the bounds.
+    fn size(&self) -> usize;
+
+    /// The position of `self` in a table for keys below `shape`. Panics if it is out of bounds.
+    fn index(&self, shape: &Self) -> usize;
+}
+
+impl DenseKey for usize {
+    fn size(&self) -> usize {
+        *self
+    }
+
+    fn index(&self, shape: &Self) -> usize {
+        assert!(self < shape, "key {self} is out of bounds {shape}");
+        *self
+    }
+}
+
+impl DenseKey for (usize, usize) {
+    fn size(&self) -> usize {
+        self.0 * self.1
+    }
+
+    fn index(&self, shape: &Self) -> usize {
+        assert!(
+            self.0 < shape.0 && self.1 < shape.1,
+            "key {self:?} is out of bounds {shape:?}"
+        );
+        self.0 * shape.1 + self.1
+    }
+}
+
+impl DenseKey for (usize, usize, usize) {
+    fn size(&self) -> usize {
+        self.0 * self.1 * self.2
+    }
+
+    fn index(&self, shape: &Self) -> usize {
+        assert!(
+            self.0 < shape.0 && self.1 < shape.1 && self.2 < shape.2,
+            "key {self:?} is out of bounds {shape:?}"
+        );
+        (self.0 * shape.1 + self.1) * shape.2 + self.2
+    }
+}
+
+/// A cache backed by a table with a slot for every key below a fixed shape.
+#[derive(Debug, Clone)]
+pub struct DenseCache<K, V> {
+    shape: K,
+    values: Vec<Option<V>>,
+}
+
+impl<K: DenseKey, V> Cache<K, V> for DenseCache<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.values[key.index(&self.shape)].as_ref()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.values[key.index(&self.shape)] = Some(value);
+    }
+}
+
+/// How often cached values were reused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    pub fn is_empty(&self) -> bool {
+        self.hits == 0 && self.misses == 0
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.hits + self.misses;
+        let rate = if total == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / total as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({rate:.1}% hit rate)",
+            self.hits, self.misses
+        )
+    }
+}
+
+static HITS: AtomicUsize = AtomicUsize::new(0);
+static MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// The hits and misses of all memos dropped since the last call, resetting the totals.
+pub fn take_stats() -> Stats {
+    Stats {
+        hits: HITS.swap(0, Ordering::Relaxed),
+        misses: MISSES.swap(0, Ordering::Relaxed),
+    }
+}
+
+/// Cached results of a recursive function, by argument key.
+#[derive(Debug)]
+pub struct Memo<K, V, C = HashCache<K, V>> {
+    cache: C,
+    stats: Stats,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_cache(HashCache(HashMap::new()))
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: DenseKey, V> Memo<K, V, DenseCache<K, V>> {
+    /// A memo for the keys below `shape`, e.g. `(rows, columns)` for pairs of indices.
+    pub fn dense(shape: K) -> Self {
+        let mut values = Vec::new();
+        values.resize_with(shape.size(), || None);
+        Self::with_cache(DenseCache { shape, values })
+    }
+}
+
+impl<K, V, C: Cache<K, V>> Memo<K, V, C> {
+    pub fn with_cache(cache: C) -> Self {
+        Memo {
+            cache,
+            stats: Stats::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The value cached for `key`, or else the one returned by `compute`, which gets the memo
+    /// back for making recursive calls.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+impl<K, V, C> Drop for Memo<K, V, C> {
+    fn drop(&mut self) {
+        HITS.fetch_add(self.stats.hits, Ordering::Relaxed);
+        MISSES.fetch_add(self.stats.misses, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cache, DenseKey, Memo, Stats};
+
+    /// The number of monotonic lattice paths from `(i, j)` to the axes.
+    fn paths<C: Cache<(usize, usize), u64>>(
+        memo: &mut Memo<(usize, usize), u64, C>,
+        i: usize,
+        j: usize,
+    ) -> u64 {
+        memo.get_or_compute((i, j), |memo| {
+            if i == 0 || j == 0 {
+                1
+            } else {
+                paths(memo, i - 1, j) + paths(memo, i, j - 1)
+            }
+        })
+    }
+
+    #[test]
+    fn caches_by_key() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601080390);
+        // the 16 * 16 inner cells make two calls each, and only the corner (0, 0) is never reached.
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 2 * 16 * 16 + 1 - (17 * 17 - 1),
+                misses: 17 * 17 - 1
+            }
+        );
+
+        let mut dense = Memo::dense((17, 17));
+        assert_eq!(paths(&mut dense, 16, 16), 601080390);
+        assert_eq!(dense.stats(), memo.stats());
+    }
+
+    #[test]
+    fn indexes_dense_keys() {
+        let shape = (2, 3, 4);
+        assert_eq!(shape.size(), 24);
+        assert_eq!((0, 0, 0).index(&shape), 0);
+        assert_eq!((1, 2, 3).index(&shape), 23);
+        assert_eq!((1, 0, 2).index(&shape), 14);
+        assert_eq!((1, 2).index(&(2, 3)), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn rejects_keys_out_of_bounds() {
+        Memo::<_, u8, _>::dense((2, 3)).get_or_compute((0, 3), |_| 0);
+    }
+
+    #[test]
+    fn reports_dropped_stats() {
+        let mut memo = Memo::<u8, u8>::new();
+        memo.get_or_compute(1, |_| 1);
+        memo.get_or_compute(1, |_| 1);
+        drop(memo);
+        // other tests run in parallel, so only check that this memo was counted.
+        let stats = super::take_stats();
+        assert!(stats.hits >= 1 && stats.misses >= 1);
+    }
+}
@@ -0,0 +1,290 @@
+/// Parsers for the shapes that puzzle inputs usually come in, built on [`nom`].
+///
+/// Import everything with `use advent_of_code::parsing::*;`, which also brings the most common nom
+/// combinators into scope. Parsers are run with [`parse_all`], which turns nom's errors into a
+/// [`ParseError`] pointing at the line and column where parsing failed.
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use ndarray::Array2;
+use nom::character::complete::{digit1, one_of};
+use nom::combinator::{all_consuming, map_res, opt, recognize};
+use nom::error::{ErrorKind, ParseError as _};
+use nom::IResult;
+
+use crate::grid::Grid;
+
+pub use nom::branch::alt;
+pub use nom::bytes::complete::tag;
+pub use nom::character::complete::{alpha1, char, line_ending, space0, space1};
+pub use nom::combinator::{map, value};
+pub use nom::multi::{many1, separated_list0, separated_list1};
+pub use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
+pub use nom::Parser;
+
+/// The result of the parsers in this module.
+pub type PResult<'a, T> = IResult<&'a str, T>;
+
+/// Where and why a parser failed. Lines and columns are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// The nom parser that failed.
+    pub kind: ErrorKind,
+    /// The rest of the line where the parser failed.
+    pub found: String,
+}
+
+impl ParseError {
+    fn at(input: &str, rest: &str, kind: ErrorKind) -> Self {
+        let offset = input.len() - rest.len();
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            kind,
+            found: rest.lines().next().unwrap_or("").to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "parse error at {}:{} ({}) near {:?}",
+            self.line,
+            self.column,
+            self.kind.description(),
+            self.found
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Runs `parser` on the whole input, allowing only trailing whitespace after it.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    parser: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> Result<T, ParseError> {
+    let mut parser = all_consuming(terminated(parser, nom::character::complete::multispace0));
+    match parser(input) {
+        Ok((_, result)) => Ok(result),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::at(input, e.input, e.code))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(input, "", ErrorKind::Eof)),
+    }
+}
+
+/// An integer without a sign.
+pub fn unsigned<T: FromStr>(input: &str) -> PResult<'_, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// An integer with an optional `+` or `-` sign. Any type parsed from such text works, e.g. `f64`.
+pub fn signed<T: FromStr>(input: &str) -> PResult<'_, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// One or more signed numbers separated by `sep`, e.g. `numbers(char(','))` for `1,-2,3`.
+pub fn numbers<'a, T: FromStr, S>(
+    sep: impl Parser<&'a str, S, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(sep, signed)
+}
+
+/// One or more signed numbers separated by spaces, and possibly preceded by some, as in
+/// `  7  15   30`.
+pub fn spaced_numbers<T: FromStr>(input: &str) -> PResult<'_, Vec<T>> {
+    preceded(space0, numbers(space1))(input)
+}
+
+/// Exactly `N` items separated by `sep`, e.g. the coordinates of a point.
+pub fn array<'a, const N: usize, T, S>(
+    item: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+    sep: impl Parser<&'a str, S, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, [T; N]> {
+    map_res(separated_list1(sep, item), <[T; N]>::try_from)
+}
+
+/// One or more items on consecutive lines.
+pub fn lines<'a, T>(
+    item: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(line_ending, item)
+}
+
+/// An empty line, which is the line ending of the previous line followed by its own.
+pub fn blank_line(input: &str) -> PResult<'_, (&str, &str)> {
+    pair(line_ending, line_ending)(input)
+}
+
+/// One or more blocks separated by blank lines.
+pub fn blocks<'a, T>(
+    block: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(blank_line, block)
+}
+
+/// A `key: value` line, returning the value.
+pub fn section<'a, T>(
+    key: &'static str,
+    value: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, T> {
+    preceded(tuple((tag(key), char(':'), space0)), value)
+}
+
+/// A `name -> a, b` line, returning the name and its targets.
+pub fn edges<'a, T>(
+    mut name: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, (T, Vec<T>)> {
+    move |input| {
+        let (input, from) = name.parse(input)?;
+        let (input, _) = tag(" -> ")(input)?;
+        let (input, to) = separated_list1(tag(", "), |i| name.parse(i))(input)?;
+        Ok((input, (from, to)))
+    }
+}
+
+/// A block of non-empty lines of the same width, mapping every character to a cell. The block ends
+/// at a blank line or at the end of the input.
+pub fn grid<'a, T>(
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Grid<T>> {
+    move |input| {
+        // once a cell was parsed this is certainly a grid, so errors are not recoverable anymore.
+        let fail = |rest, kind, started: bool| {
+            let e = nom::error::Error::from_error_kind(rest, kind);
+            Err(if started {
+                nom::Err::Failure(e)
+            } else {
+                nom::Err::Error(e)
+            })
+        };
+
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        let mut rest = input;
+        loop {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            let line = rest[..end].trim_end_matches('\r');
+            if line.is_empty() {
+                break;
+            }
+            for (j, c) in line.char_indices() {
+                match cell(c) {
+                    Some(v) => cells.push(v),
+                    None => return fail(&rest[j..], ErrorKind::Char, !cells.is_empty()),
+                }
+            }
+            if *width.get_or_insert(line.chars().count()) != line.chars().count() {
+                return fail(rest, ErrorKind::Verify, true);
+            }
+            height += 1;
+
+            // only consume the line ending when another row follows.
+            rest = &rest[end..];
+            match rest.strip_prefix('\n') {
+                Some(next) if !next.trim_start_matches('\r').starts_with('\n') => rest = next,
+                _ => break,
+            }
+        }
+
+        match width {
+            Some(width) => {
+                let cells = Array2::from_shape_vec((height, width), cells).unwrap();
+                Ok((rest, Grid::from(cells)))
+            }
+            None => fail(rest, ErrorKind::Many1, false),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse_all("42", unsigned::<u32>), Ok(42));
+        assert_eq!(parse_all("-42", signed::<i64>), Ok(-42));
+        assert_eq!(
+            parse_all("1,-2,+3", numbers::<i32, _>(char(','))),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(
+            parse_all("  7  15   30", spaced_numbers::<u32>),
+            Ok(vec![7, 15, 30])
+        );
+        assert_eq!(
+            parse_all(
+                "19, 13, 30",
+                array::<3, f64, _>(signed, pair(char(','), space1))
+            ),
+            Ok([19.0, 13.0, 30.0])
+        );
+        assert!(parse_all("1,2", array::<3, u32, _>(unsigned, char(','))).is_err());
+        assert!(parse_all("-1", unsigned::<u32>).is_err());
+    }
+
+    #[test]
+    fn parses_lines_and_blocks() {
+        let input = "Time:      7  15\nDistance:  9  40\n\n1 2\n3\n\n4\n";
+        let (time, distance, rest) = parse_all(
+            input,
+            tuple((
+                terminated(section("Time", spaced_numbers::<u32>), line_ending),
+                section("Distance", spaced_numbers::<u32>),
+                preceded(blank_line, blocks(lines(spaced_numbers::<u32>))),
+            )),
+        )
+        .unwrap();
+        assert_eq!(time, vec![7, 15]);
+        assert_eq!(distance, vec![9, 40]);
+        assert_eq!(rest, vec![vec![vec![1, 2], vec![3]], vec![vec![4]]]);
+    }
+
+    #[test]
+    fn parses_edges() {
+        assert_eq!(
+            parse_all("a -> bb, c", edges(alpha1)),
+            Ok(("a", vec!["bb", "c"]))
+        );
+    }
+
+    #[test]
+    fn parses_grids() {
+        let cell = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        let grids = parse_all("#.\n.#\n\n##\n", blocks(grid(cell))).unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[0].size(), (2, 2));
+        assert!(grids[0][(1, 1)] && !grids[0][(1, 0)]);
+        assert_eq!(grids[1].size(), (1, 2));
+
+        let e = parse_all("#.\n#x", grid(cell)).unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (2, 2, ErrorKind::Char));
+        let e = parse_all("#.\n\n#.\n#", blocks(grid(cell))).unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (4, 1, ErrorKind::Verify));
+    }
+
+    #[test]
+    fn reports_positions() {
+        let e = parse_all("1 2\n3 x\n", lines(spaced_numbers::<u32>)).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert_eq!(e.found, "x");
+        assert_eq!(e.to_string(), "parse error at 2:3 (End of file) near \"x\"");
+
+        let e = parse_all("99999999999", unsigned::<u32>).unwrap_err();
+        assert_eq!((e.line, e.column, e.kind), (1, 1, ErrorKind::MapRes));
+    }
+}
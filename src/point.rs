@@ -0,0 +1,297 @@
+/// Integer points, vectors and axis-aligned boxes in two and three dimensions.
+///
+/// A [`Point2`] doubles as a grid position: `x` is the first coordinate, i.e. the row `i` of a
+/// [`Grid`](crate::grid::Grid), and `y` the column `j`. Points are also used as the vectors between
+/// them, so they add, subtract and scale. Boxes include both of their corners, like the bricks and
+/// ranges in puzzle inputs.
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use num::PrimInt;
+
+use crate::direction::Direction;
+
+macro_rules! impl_point {
+    ($point:ident, $n:literal: $($c:ident),+) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $point<T> {
+            $(pub $c: T,)+
+        }
+
+        impl<T> $point<T> {
+            pub const fn new($($c: T),+) -> Self {
+                $point { $($c),+ }
+            }
+
+            /// Applies `f` to every coordinate.
+            pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> $point<U> {
+                $point { $($c: f(self.$c)),+ }
+            }
+
+            /// Converts every coordinate, or returns `None` if one does not fit.
+            pub fn try_cast<U: TryFrom<T>>(self) -> Option<$point<U>> {
+                Some($point { $($c: U::try_from(self.$c).ok()?),+ })
+            }
+        }
+
+        impl<T: PrimInt> $point<T> {
+            /// The sum of the distances along every axis.
+            pub fn manhattan(self, other: Self) -> T {
+                T::zero() $(+ abs_diff(self.$c, other.$c))+
+            }
+
+            /// The largest of the distances along every axis, i.e. the number of king moves.
+            pub fn chebyshev(self, other: Self) -> T {
+                T::zero() $(.max(abs_diff(self.$c, other.$c)))+
+            }
+
+            /// The smallest coordinates of both points.
+            pub fn min(self, other: Self) -> Self {
+                $point { $($c: self.$c.min(other.$c)),+ }
+            }
+
+            /// The largest coordinates of both points.
+            pub fn max(self, other: Self) -> Self {
+                $point { $($c: self.$c.max(other.$c)),+ }
+            }
+        }
+
+        impl<T> From<[T; $n]> for $point<T> {
+            fn from([$($c),+]: [T; $n]) -> Self {
+                $point { $($c),+ }
+            }
+        }
+
+        impl<T> From<$point<T>> for [T; $n] {
+            fn from(p: $point<T>) -> Self {
+                [$(p.$c),+]
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($c: self.$c + other.$c),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($c: self.$c - other.$c),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($c: -self.$c),+ }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, k: T) -> Self {
+                $point { $($c: self.$c * k),+ }
+            }
+        }
+
+        impl<T: Add<Output = T> + Copy> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Sub<Output = T> + Copy> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    a.max(b) - a.min(b)
+}
+
+impl_point!(Point2, 2: x, y);
+impl_point!(Point3, 3: x, y, z);
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + TryInto<usize>> Point2<T> {
+    /// The grid position `(i, j)` of the point, or `None` if a coordinate is negative or too large.
+    pub fn to_index(self) -> Option<(usize, usize)> {
+        Some((self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+impl<T: TryFrom<usize>> Point2<T> {
+    /// The point at grid position `(i, j)`, or `None` if it does not fit in `T`.
+    pub fn from_index((i, j): (usize, usize)) -> Option<Self> {
+        Some(Point2::new(T::try_from(i).ok()?, T::try_from(j).ok()?))
+    }
+}
+
+impl From<Direction> for Point2<isize> {
+    /// One step in the direction, as a vector in grid coordinates.
+    fn from(d: Direction) -> Self {
+        d.delta().into()
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! impl_box {
+    ($bx:ident, $point:ident: $($c:ident),+) => {
+        /// An axis-aligned box, including both corners.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $bx<T> {
+            pub min: $point<T>,
+            pub max: $point<T>,
+        }
+
+        impl<T: PrimInt> $bx<T> {
+            /// The box spanned by two opposite corners, in any order.
+            pub fn new(a: $point<T>, b: $point<T>) -> Self {
+                $bx { min: a.min(b), max: a.max(b) }
+            }
+
+            pub fn contains(&self, p: $point<T>) -> bool {
+                $(self.min.$c <= p.$c && p.$c <= self.max.$c)&&+
+            }
+
+            /// Whether the boxes share at least one point.
+            pub fn intersects(&self, other: &Self) -> bool {
+                $(self.min.$c <= other.max.$c && other.min.$c <= self.max.$c)&&+
+            }
+
+            /// The points in both boxes, if there are any.
+            pub fn intersection(&self, other: &Self) -> Option<Self> {
+                self.intersects(other).then(|| $bx {
+                    min: self.min.max(other.min),
+                    max: self.max.min(other.max),
+                })
+            }
+
+            /// The number of points along every axis.
+            pub fn extent(&self) -> $point<T> {
+                $point { $($c: self.max.$c - self.min.$c + T::one()),+ }
+            }
+
+            /// The number of points in the box.
+            pub fn size(&self) -> T {
+                T::one() $(* (self.max.$c - self.min.$c + T::one()))+
+            }
+
+            /// Moves the box by `offset`.
+            pub fn translate(&self, offset: $point<T>) -> Self {
+                $bx { min: self.min + offset, max: self.max + offset }
+            }
+        }
+    };
+}
+
+impl_box!(Box2, Point2: x, y);
+impl_box!(Box3, Point3: x, y, z);
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Box2, Box3, Point2, Point3};
+    use crate::direction::Direction;
+
+    #[test]
+    fn does_arithmetic() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 5);
+        assert_eq!(a + b, Point2::new(-2, 3));
+        assert_eq!(a - b, Point2::new(4, -7));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!(a * 3, Point2::new(3, -6));
+
+        let mut c = Point3::new(1u8, 2, 3);
+        c += Point3::new(1, 1, 1);
+        c -= Point3::new(2, 0, 1);
+        assert_eq!(c, Point3::from([0, 3, 3]));
+        assert_eq!(<[u8; 3]>::from(c), [0, 3, 3]);
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 5);
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 7);
+
+        // unsigned coordinates, in either order.
+        let a = Point3::new(2usize, 9, 4);
+        let b = Point3::new(5usize, 1, 4);
+        assert_eq!((a.manhattan(b), b.manhattan(a)), (11, 11));
+        assert_eq!(a.chebyshev(b), 8);
+    }
+
+    #[test]
+    fn converts_to_grid_indices() {
+        assert_eq!(Point2::new(3i32, 4).to_index(), Some((3, 4)));
+        assert_eq!(Point2::new(-1i32, 4).to_index(), None);
+        assert_eq!(Point2::<u8>::from_index((3, 300)), None);
+        assert_eq!(
+            Point2::<i64>::from_index((3, 300)),
+            Some(Point2::new(3, 300))
+        );
+        assert_eq!(Point2::new(-1i64, 2).try_cast::<u32>(), None);
+        assert_eq!(Point2::from(Direction::SW), Point2::new(1, -1));
+    }
+
+    #[test]
+    fn intersects_boxes() {
+        let a = Box3::new(Point3::new(0, 0, 2), Point3::new(2, 0, 2));
+        let b = Box3::new(Point3::new(1, 2, 2), Point3::new(1, 0, 4));
+        assert_eq!(a.size(), 3);
+        assert_eq!(b.extent(), Point3::new(1, 3, 3));
+        assert!(a.intersects(&b));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Box3::new(Point3::new(1, 0, 2), Point3::new(1, 0, 2)))
+        );
+        let above = a.translate(Point3::new(0, 0, 1));
+        assert!(!above.intersects(&a));
+        assert_eq!(above.intersection(&a), None);
+        assert!(above.contains(Point3::new(2, 0, 3)));
+
+        // boxes that only touch at a corner still share it.
+        let a = Box2::new(Point2::new(0, 0), Point2::new(1, 1));
+        let b = Box2::new(Point2::new(2, 2), Point2::new(1, 1));
+        assert_eq!(a.intersection(&b).map(|b| b.size()), Some(1));
+    }
+}
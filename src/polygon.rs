@@ -0,0 +1,172 @@
+/// Polygons on the integer lattice, for counting the cells enclosed by a loop.
+///
+/// The area comes from the shoelace formula and the lattice points from Pick's theorem, both exact:
+/// coordinates are `i64` and every product and sum is computed in `i128`, panicking instead of
+/// overflowing. Polygons must not cross themselves.
+use crate::direction::Direction;
+
+/// A point as `(row, column)`, like the positions of a grid.
+pub type Point = (i64, i64);
+
+/// A closed polygon, given by its vertices in order. The last vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+fn checked_sum(values: impl Iterator<Item = i128>) -> i128 {
+    values.fold(0i128, |acc, v| {
+        acc.checked_add(v).expect("polygon is too large for i128")
+    })
+}
+
+impl Polygon {
+    /// The polygon through `vertices`. The loop may be closed explicitly by repeating the first
+    /// vertex at the end.
+    pub fn from_vertices(vertices: impl IntoIterator<Item = Point>) -> Self {
+        let mut vertices = vertices.into_iter().collect::<Vec<_>>();
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Polygon { vertices }
+    }
+
+    /// The polygon traced by moving from `(0, 0)`, which must end where it started.
+    pub fn from_moves(moves: impl IntoIterator<Item = (Direction, i64)>) -> Self {
+        let mut vertices = vec![(0, 0)];
+        let mut curr: Point = (0, 0);
+        for (d, n) in moves {
+            let (di, dj) = d.delta();
+            curr = (
+                n.checked_mul(di as i64)
+                    .and_then(|di| curr.0.checked_add(di))
+                    .expect("move overflows i64"),
+                n.checked_mul(dj as i64)
+                    .and_then(|dj| curr.1.checked_add(dj))
+                    .expect("move overflows i64"),
+            );
+            vertices.push(curr);
+        }
+        assert_eq!(curr, (0, 0), "the moves do not form a loop");
+        Self::from_vertices(vertices)
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Twice the enclosed area, which is always an integer.
+    pub fn double_area(&self) -> i128 {
+        let sum = checked_sum(
+            self.edges()
+                .map(|((i0, j0), (i1, j1))| i0 as i128 * j1 as i128 - i1 as i128 * j0 as i128),
+        );
+        sum.abs()
+    }
+
+    /// The enclosed area, rounded down when the polygon has diagonal edges.
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// The number of lattice points on the edges, counting each one once per edge through it.
+    fn edge_points(&self) -> i128 {
+        checked_sum(self.edges().map(|((i0, j0), (i1, j1))| {
+            let di = (i1 as i128 - i0 as i128).unsigned_abs();
+            let dj = (j1 as i128 - j0 as i128).unsigned_abs();
+            num::integer::gcd(di, dj) as i128
+        }))
+    }
+
+    /// The number of lattice points on or inside the polygon, i.e. the cells covered by a loop
+    /// drawn through the centers of cells, including the loop itself.
+    pub fn covered_points(&self) -> i128 {
+        if self.vertices.is_empty() {
+            return 0;
+        }
+        // Pick's theorem, A = I + B/2 - 1, gives I + B = A + B/2 + 1. It also holds when there is
+        // no area and the edges go back and forth over the same points, which are then counted
+        // twice.
+        (self.double_area() + self.edge_points()) / 2 + 1
+    }
+
+    /// The number of lattice points strictly inside the polygon.
+    pub fn interior_points(&self) -> i128 {
+        if self.double_area() == 0 {
+            return 0;
+        }
+        // Pick's theorem again, I = A - B/2 + 1.
+        (self.double_area() - self.edge_points()) / 2 + 1
+    }
+
+    /// The number of lattice points on the boundary.
+    pub fn boundary_points(&self) -> i128 {
+        self.covered_points() - self.interior_points()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Polygon;
+    use crate::direction::Direction;
+
+    fn counts(p: &Polygon) -> (i128, i128, i128, i128) {
+        (
+            p.double_area(),
+            p.boundary_points(),
+            p.interior_points(),
+            p.covered_points(),
+        )
+    }
+
+    #[test]
+    fn counts_square() {
+        let square = Polygon::from_vertices([(0, 0), (0, 3), (3, 3), (3, 0)]);
+        assert_eq!(counts(&square), (18, 12, 4, 16));
+        assert_eq!(square.area(), 9);
+
+        // same square, clockwise instead of counter-clockwise and explicitly closed.
+        let square = Polygon::from_vertices([(0, 0), (3, 0), (3, 3), (0, 3), (0, 0)]);
+        assert_eq!(square.vertices().len(), 4);
+        assert_eq!(counts(&square), (18, 12, 4, 16));
+    }
+
+    #[test]
+    fn counts_diagonal_edges() {
+        let triangle = Polygon::from_vertices([(0, 0), (4, 0), (0, 3)]);
+        assert_eq!(counts(&triangle), (12, 8, 3, 11));
+        assert_eq!(triangle.area(), 6);
+    }
+
+    #[test]
+    fn follows_moves() {
+        use Direction::*;
+        // an L-shaped loop through 8 cells, which leaves none inside.
+        let l = Polygon::from_moves([(E, 2), (S, 1), (W, 1), (S, 1), (W, 1), (N, 2)]);
+        assert_eq!(counts(&l), (6, 8, 0, 8));
+    }
+
+    #[test]
+    fn handles_degenerate_shapes() {
+        assert_eq!(counts(&Polygon::from_vertices([])), (0, 0, 0, 0));
+        assert_eq!(counts(&Polygon::from_vertices([(5, 5)])), (0, 1, 0, 1));
+        let segment = Polygon::from_moves([(Direction::E, 3), (Direction::W, 3)]);
+        assert_eq!(counts(&segment), (0, 4, 0, 4));
+        let still = Polygon::from_moves([(Direction::N, 0)]);
+        assert_eq!(counts(&still), (0, 1, 0, 1));
+    }
+
+    #[test]
+    fn handles_huge_coordinates() {
+        let n = 1 << 40;
+        let square = Polygon::from_vertices([(0, 0), (0, n), (n, n), (n, 0)]);
+        let n = n as i128;
+        assert_eq!(square.area(), n * n);
+        assert_eq!(square.covered_points(), (n + 1) * (n + 1));
+    }
+}
@@ -0,0 +1,204 @@
+/// Fast extraction of the integers in a puzzle input.
+///
+/// Many inputs are just numbers separated by some punctuation. Instead of splitting the text and
+/// parsing every token, [`integers`] walks the bytes once without allocating, skipping separators
+/// eight bytes at a time.
+use std::marker::PhantomData;
+
+/// An integer type the scanner can produce.
+pub trait Integer: Copy {
+    /// Whether a `-` right before the digits makes the value negative.
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// Appends a decimal digit, wrapping on overflow.
+    fn push_digit(self, digit: u8) -> Self;
+
+    fn negate(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($signed:literal: $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn push_digit(self, digit: u8) -> Self {
+                    self.wrapping_mul(10).wrapping_add(digit as Self)
+                }
+
+                #[inline]
+                fn negate(self) -> Self {
+                    self.wrapping_neg()
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(true: i8, i16, i32, i64, i128, isize);
+impl_integer!(false: u8, u16, u32, u64, u128, usize);
+
+const ONES: u64 = u64::from_ne_bytes([1; 8]);
+const HIGHS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+/// Whether any of the eight bytes of `word` is an ASCII digit.
+#[inline]
+fn has_digit(word: u64) -> bool {
+    // digits become the only bytes below 10, which the subtraction detects by borrowing into the
+    // high bit of the byte.
+    let x = word ^ (ONES * b'0' as u64);
+    x.wrapping_sub(ONES * 10) & !x & HIGHS != 0
+}
+
+/// An iterator over the integers in some bytes, see [`integers`].
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    _marker: PhantomData<T>,
+}
+
+/// The integers in `bytes`, in order. Every run of ASCII digits is an integer, and for signed types
+/// a `-` right before the digits makes it negative. Everything else is a separator.
+pub fn integers<T: Integer>(bytes: &[u8]) -> Integers<'_, T> {
+    Integers {
+        bytes,
+        pos: 0,
+        _marker: PhantomData,
+    }
+}
+
+impl<'a, T: Integer> Integers<'a, T> {
+    /// Moves to the next digit, or to the end of the bytes.
+    #[inline]
+    fn skip_separators(&mut self) {
+        while let Some(chunk) = self.bytes.get(self.pos..self.pos + 8) {
+            if has_digit(u64::from_ne_bytes(chunk.try_into().unwrap())) {
+                break;
+            }
+            self.pos += 8;
+        }
+        while self.pos < self.bytes.len() && !self.bytes[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+    }
+
+    /// The next `N` integers, or `None` if there are fewer left.
+    pub fn next_array<const N: usize>(&mut self) -> Option<[T; N]> {
+        let mut result = [T::ZERO; N];
+        for v in result.iter_mut() {
+            *v = self.next()?;
+        }
+        Some(result)
+    }
+
+    /// Groups the integers by `N`, e.g. the coordinates of points. An incomplete group at the end
+    /// is dropped.
+    pub fn arrays<const N: usize>(self) -> Arrays<'a, T, N> {
+        Arrays { integers: self }
+    }
+}
+
+impl<T: Integer> Iterator for Integers<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.skip_separators();
+        if self.pos >= self.bytes.len() {
+            return None;
+        }
+
+        let negative = T::SIGNED && self.pos > 0 && self.bytes[self.pos - 1] == b'-';
+        let mut value = T::ZERO;
+        while let Some(&b) = self.bytes.get(self.pos).filter(|b| b.is_ascii_digit()) {
+            value = value.push_digit(b - b'0');
+            self.pos += 1;
+        }
+        Some(if negative { value.negate() } else { value })
+    }
+}
+
+/// An iterator over groups of `N` integers, see [`Integers::arrays`].
+#[derive(Debug, Clone)]
+pub struct Arrays<'a, T, const N: usize> {
+    integers: Integers<'a, T>,
+}
+
+impl<T: Integer, const N: usize> Iterator for Arrays<'_, T, N> {
+    type Item = [T; N];
+
+    fn next(&mut self) -> Option<[T; N]> {
+        self.integers.next_array()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{has_digit, integers};
+
+    #[test]
+    fn detects_digits_in_words() {
+        for i in 0..8 {
+            for b in 0..=255u8 {
+                let mut word = *b"abcdefgh";
+                word[i] = b;
+                assert_eq!(
+                    has_digit(u64::from_ne_bytes(word)),
+                    b.is_ascii_digit(),
+                    "{b} at {i}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scans_integers() {
+        let input = "19, 13, 30 @ -2,  1, -2\n18, 19, 22 @ -1, -1, -2\n";
+        let expected = input
+            .split(|c: char| !c.is_ascii_digit() && c != '-')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<i64>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            integers::<i64>(input.as_bytes()).collect::<Vec<_>>(),
+            expected
+        );
+
+        // unsigned types treat signs as separators.
+        assert_eq!(
+            integers::<u32>(b"1-3 a: 42").collect::<Vec<_>>(),
+            vec![1, 3, 42]
+        );
+        assert_eq!(integers::<u8>(b"no numbers here!").next(), None);
+        assert_eq!(integers::<u8>(b"").next(), None);
+        assert_eq!(
+            integers::<i32>(b"x=-3, y=14").collect::<Vec<_>>(),
+            vec![-3, 14]
+        );
+    }
+
+    #[test]
+    fn skips_long_separators() {
+        let input = format!("{}-1234{}5", "-".repeat(37), " :".repeat(20));
+        assert_eq!(
+            integers::<i32>(input.as_bytes()).collect::<Vec<_>>(),
+            vec![-1234, 5]
+        );
+    }
+
+    #[test]
+    fn groups_integers() {
+        let bricks = integers::<usize>(b"1,0,1~1,2,1\n0,0,2~2,0,2\n3,3")
+            .arrays::<6>()
+            .collect::<Vec<_>>();
+        assert_eq!(bricks, vec![[1, 0, 1, 1, 2, 1], [0, 0, 2, 2, 0, 2]]);
+
+        let mut it = integers::<i8>(b"1 2 3");
+        assert_eq!(it.next_array::<2>(), Some([1, 2]));
+        assert_eq!(it.next_array::<2>(), None);
+    }
+}
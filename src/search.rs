@@ -0,0 +1,308 @@
+/// Shortest-path searches over implicit graphs.
+///
+/// A search is described by its start states and a `successors` function that lists the states
+/// reachable from a given one (with the cost of the move for weighted searches). Searches accept
+/// several starts and stop at the first state accepted by the `is_goal` predicate, so multiple goals
+/// are expressed through the predicate.
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use num::Zero;
+
+use crate::grid::{Grid, Pos};
+
+/// The result of a successful search.
+#[derive(Debug, Clone)]
+pub struct Found<S, C> {
+    /// The goal that was reached.
+    pub goal: S,
+    /// The cost of the cheapest path to the goal.
+    pub cost: C,
+    parents: HashMap<S, Option<S>>,
+}
+
+impl<S: Clone + Eq + Hash, C> Found<S, C> {
+    /// The states on the cheapest path, from a start to the goal.
+    pub fn path(&self) -> Vec<S> {
+        let mut path = vec![self.goal.clone()];
+        while let Some(Some(parent)) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search, where every move costs 1.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = parents.entry(start.clone()) {
+            e.insert(None);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, cost)) = queue.pop_front() {
+        if is_goal(&state) {
+            return Some(Found {
+                goal: state,
+                cost,
+                parents,
+            });
+        }
+        for next in successors(&state) {
+            if let Entry::Vacant(e) = parents.entry(next.clone()) {
+                e.insert(Some(state.clone()));
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    None
+}
+
+/// The number of moves to every state reachable from the starts.
+pub fn bfs_distances<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = distances.entry(start.clone()) {
+            e.insert(0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        let cost = distances[&state];
+        for next in successors(&state) {
+            if let Entry::Vacant(e) = distances.entry(next.clone()) {
+                e.insert(cost + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// The number of moves to every cell of a grid reachable from the starts, moving orthogonally
+/// between cells for which `passable` holds. Unlike [`bfs_distances`], the distances are kept in a
+/// table the size of the grid, which is much faster than hashing every position. Starts that are not
+/// passable are ignored.
+pub fn grid_bfs_distances<T>(
+    grid: &Grid<T>,
+    starts: impl IntoIterator<Item = Pos>,
+    mut passable: impl FnMut(Pos, &T) -> bool,
+) -> Grid<Option<usize>> {
+    let mut distances = Grid::from_elem(grid.size(), None);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distances[start].is_none() && passable(start, &grid[start]) {
+            distances[start] = Some(0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((pos, cost)) = queue.pop_front() {
+        for next in grid.neighbours_4(pos) {
+            if distances[next].is_none() && passable(next, &grid[next]) {
+                distances[next] = Some(cost + 1);
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Dijkstra's algorithm, for moves with non-negative costs.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// The cost of the cheapest path to every state reachable from the starts.
+pub fn dijkstra_distances<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut distances = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        distances.insert(start.clone(), C::zero());
+        heap.push(Reverse((C::zero(), start)));
+    }
+
+    while let Some(Reverse((cost, state))) = heap.pop() {
+        if distances.get(&state).is_some_and(|&c| c < cost) {
+            continue; // stale entry
+        }
+        for (next, step) in successors(&state) {
+            let tentative = cost + step;
+            if distances.get(&next).is_none_or(|&c| tentative < c) {
+                distances.insert(next.clone(), tentative);
+                heap.push(Reverse((tentative, next)));
+            }
+        }
+    }
+    distances
+}
+
+/// A* search. The `heuristic` must never overestimate the remaining cost to the nearest goal.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    // best known cost and parent of every discovered state.
+    let mut best: HashMap<S, (C, Option<S>)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        best.insert(start.clone(), (C::zero(), None));
+        heap.push(Reverse((estimate, C::zero(), start)));
+    }
+
+    while let Some(Reverse((_, cost, state))) = heap.pop() {
+        if best.get(&state).is_some_and(|&(c, _)| c < cost) {
+            continue; // stale entry
+        }
+        if is_goal(&state) {
+            let parents = best.into_iter().map(|(s, (_, p))| (s, p)).collect();
+            return Some(Found {
+                goal: state,
+                cost,
+                parents,
+            });
+        }
+        for (next, step) in successors(&state) {
+            let tentative = cost + step;
+            if best.get(&next).is_none_or(|&(c, _)| tentative < c) {
+                let estimate = tentative + heuristic(&next);
+                best.insert(next.clone(), (tentative, Some(state.clone())));
+                heap.push(Reverse((estimate, tentative, next)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs, bfs_distances, dijkstra, dijkstra_distances, grid_bfs_distances};
+    use crate::grid::Grid;
+
+    // a 5x5 maze where `#` blocks, searched with 4-neighbourhoods.
+    const MAZE: [&str; 5] = [".....", "####.", ".....", ".####", "....."];
+
+    fn open_neighbours(&(i, j): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)]
+            .into_iter()
+            .filter(|&(i, j)| {
+                (0..5).contains(&i)
+                    && (0..5).contains(&j)
+                    && MAZE[i as usize].as_bytes()[j as usize] == b'.'
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let found = bfs([(0, 0)], open_neighbours, |&p| p == (4, 4)).unwrap();
+        assert_eq!(found.cost, 16);
+        let path = found.path();
+        assert_eq!(path.len(), 17);
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path[5], (1, 4));
+        assert_eq!(*path.last().unwrap(), (4, 4));
+    }
+
+    #[test]
+    fn bfs_stops_at_nearest_goal() {
+        let goals = [(2, 0), (4, 4)];
+        let found = bfs([(0, 0)], open_neighbours, |p| goals.contains(p)).unwrap();
+        assert_eq!((found.goal, found.cost), ((2, 0), 10));
+        assert!(bfs([(0, 0)], open_neighbours, |&p| p == (1, 0)).is_none());
+    }
+
+    #[test]
+    fn bfs_maps_distances_from_several_starts() {
+        let distances = bfs_distances([(0, 0), (4, 4)], open_neighbours);
+        assert_eq!(distances.len(), 17);
+        assert_eq!(distances[&(2, 2)], 8);
+        assert_eq!(distances[&(4, 0)], 4);
+    }
+
+    #[test]
+    fn bfs_maps_grid_distances() {
+        let maze = Grid::parse(&MAZE.join("\n"), |c| Some(c == '#')).unwrap();
+        let distances = grid_bfs_distances(&maze, [(0, 0), (4, 4), (1, 0)], |_, &wall| !wall);
+        let reached = distances.iter().flatten().count();
+        assert_eq!(
+            reached,
+            bfs_distances([(0, 0), (4, 4)], open_neighbours).len()
+        );
+        assert_eq!(distances[(2, 2)], Some(8));
+        assert_eq!(distances[(4, 0)], Some(4));
+        assert_eq!(distances[(1, 0)], None);
+    }
+
+    // moving right costs 1, moving down costs the row number.
+    fn weighted(&(i, j): &(u32, u32)) -> Vec<((u32, u32), u32)> {
+        let mut next = vec![];
+        if j < 4 {
+            next.push(((i, j + 1), 1));
+        }
+        if i < 4 {
+            next.push(((i + 1, j), i + 1));
+        }
+        next
+    }
+
+    #[test]
+    fn weighted_searches_agree() {
+        let goal = |p: &(u32, u32)| *p == (4, 4);
+        let by_dijkstra = dijkstra([(0, 0)], weighted, goal).unwrap();
+        let by_astar = astar([(0, 0)], weighted, |&(i, j)| (4 - i) + (4 - j), goal).unwrap();
+
+        assert_eq!(by_dijkstra.cost, 14);
+        assert_eq!(by_astar.cost, 14);
+        assert_eq!(by_astar.path().len(), 9);
+        assert_eq!(dijkstra_distances([(0, 0)], weighted)[&(4, 4)], 14);
+    }
+}
@@ -0,0 +1,119 @@
+/// A polynomial through samples at consecutive indices `0, 1, 2, ...`, for extrapolating sequences.
+///
+/// The polynomial is kept in Newton's forward-difference form, `p(x) = Σ Δᵏy₀ · C(x, k)`. Both the
+/// differences and the binomial coefficients are integers for any integer `x`, so evaluating never
+/// divides with a remainder, and everything is computed in `i128`, panicking instead of
+/// overflowing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// The leading differences `Δᵏy₀`, up to the degree of the polynomial.
+    differences: Vec<i128>,
+}
+
+/// The binomial coefficient `C(x, k)` for any integer `x`, which is negative when going backwards.
+fn binomial(x: i128, k: usize) -> i128 {
+    // the product of k consecutive integers is divisible by k!, so every division is exact.
+    (0..k as i128).fold(1i128, |acc, i| {
+        acc.checked_mul(x - i).expect("value overflows i128") / (i + 1)
+    })
+}
+
+impl Polynomial {
+    /// Fits the polynomial of smallest degree through `samples`, if the differences become constant
+    /// before running out of samples. Seeing that they do takes at least `degree + 2` samples.
+    pub fn fit<T: Copy + Into<i128>>(samples: &[T]) -> Option<Self> {
+        let mut row = samples.iter().map(|&x| x.into()).collect::<Vec<i128>>();
+        let mut differences = Vec::new();
+        while row.len() >= 2 {
+            differences.push(row[0]);
+            if row.iter().all(|&x| x == row[0]) {
+                return Some(Polynomial { differences });
+            }
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+        None
+    }
+
+    /// The polynomial of degree `samples.len() - 1` through all the samples, e.g. the quadratic
+    /// through three of them, for sequences that are known to follow one.
+    pub fn interpolate<T: Copy + Into<i128>>(samples: &[T]) -> Self {
+        let mut row = samples.iter().map(|&x| x.into()).collect::<Vec<i128>>();
+        let mut differences = Vec::new();
+        while let Some(&first) = row.first() {
+            differences.push(first);
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+        Polynomial { differences }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    /// The value at index `x`, where the samples are at `0, 1, 2, ...`. Negative indices go
+    /// backwards from the first sample.
+    pub fn eval(&self, x: i128) -> i128 {
+        self.differences
+            .iter()
+            .enumerate()
+            .map(|(k, &d)| d.checked_mul(binomial(x, k)).expect("value overflows i128"))
+            .fold(0i128, |acc, v| {
+                acc.checked_add(v).expect("value overflows i128")
+            })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{binomial, Polynomial};
+
+    #[test]
+    fn computes_binomials() {
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(2, 3), 0);
+        assert_eq!(binomial(-1, 2), 1);
+        assert_eq!(binomial(-2, 3), -4);
+    }
+
+    #[test]
+    fn extrapolates_both_ways() {
+        // the sequences of 2023 day 9.
+        for (samples, next, previous) in [
+            (vec![0i64, 3, 6, 9, 12, 15], 18, -3),
+            (vec![1, 3, 6, 10, 15, 21], 28, 0),
+            (vec![10, 13, 16, 21, 30, 45], 68, 5),
+        ] {
+            let p = Polynomial::fit(&samples).unwrap();
+            assert_eq!(p.eval(samples.len() as i128), next);
+            assert_eq!(p.eval(-1), previous);
+            for (x, &y) in samples.iter().enumerate() {
+                assert_eq!(p.eval(x as i128), y as i128);
+            }
+        }
+    }
+
+    #[test]
+    fn detects_degree() {
+        assert_eq!(Polynomial::fit(&[7, 7]).unwrap().degree(), 0);
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).unwrap().eval(100), 0);
+        assert_eq!(Polynomial::fit(&[1, 4, 9, 16]).unwrap().degree(), 2);
+        // not enough samples to tell whether the differences stay constant.
+        assert_eq!(Polynomial::fit(&[1, 2, 4]), None);
+        assert_eq!(Polynomial::fit(&[1]), None);
+        assert_eq!(Polynomial::fit::<i32>(&[]), None);
+    }
+
+    #[test]
+    fn interpolates_without_truncating() {
+        // triangular numbers have an odd second difference, which halving would truncate.
+        let p = Polynomial::interpolate(&[0, 1, 3]);
+        assert_eq!(p.degree(), 2);
+        assert_eq!(p.eval(10), 55);
+        assert_eq!(p.eval(-3), 3);
+
+        let squares = Polynomial::interpolate(&[0u64, 1, 4]);
+        let x = 1_000_000_000_000_000;
+        assert_eq!(squares.eval(x), x * x);
+    }
+}
@@ -1,9 +1,14 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
+    env,
     fmt::Display,
-    process::{Command, Output, Stdio},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Output, Stdio},
 };
 
+use crate::template::{config, is_quiet};
 use crate::Day;
 
 #[derive(Debug)]
@@ -12,6 +17,9 @@ pub enum AocCommandError {
     CommandNotCallable,
     BadExitStatus(Output),
     IoError,
+    NoSession(String),
+    CurlNotCallable,
+    CurlBadExitStatus(ExitStatus),
 }
 
 impl Display for AocCommandError {
@@ -23,6 +31,16 @@ impl Display for AocCommandError {
                 write!(f, "aoc-cli exited with a non-zero status.")
             }
             AocCommandError::IoError => write!(f, "could not write output files to file system."),
+            AocCommandError::NoSession(reason) => {
+                write!(f, "could not find a session cookie: {reason}.")
+            }
+            AocCommandError::CurlNotCallable => {
+                write!(
+                    f,
+                    "curl is not present in environment or could not be called."
+                )
+            }
+            AocCommandError::CurlBadExitStatus(status) => write!(f, "curl exited with {status}."),
         }
     }
 }
@@ -68,9 +86,11 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
     );
 
     let output = call_aoc_cli(&args)?;
-    println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
-    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    if !is_quiet() {
+        println!("---");
+        println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+        println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    }
     Ok(output)
 }
 
@@ -82,19 +102,79 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     call_aoc_cli(&args)
 }
 
+/// Downloads the JSON of a private leaderboard to `output`. aoc-cli can only print leaderboards
+/// as a table, so this uses curl with the session cookie that aoc-cli would use.
+pub fn fetch_private_leaderboard(year: u16, id: u64, output: &Path) -> Result<(), AocCommandError> {
+    let session = session_cookie()?;
+    let url = format!("https://adventofcode.com/{year}/leaderboard/private/view/{id}.json");
+
+    // the cookie is passed through stdin so that it does not show up in the process list.
+    let mut child = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--config", "-"])
+        .arg("--user-agent")
+        .arg("github.com/fspoettel/advent-of-code-rust leaderboard command")
+        .arg("--output")
+        .arg(output)
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|_| AocCommandError::CurlNotCallable)?;
+
+    child
+        .stdin
+        .take()
+        .ok_or(AocCommandError::CurlNotCallable)?
+        .write_all(format!("cookie = \"session={session}\"\n").as_bytes())
+        .map_err(|_| AocCommandError::CurlNotCallable)?;
+
+    let status = child.wait().map_err(|_| AocCommandError::CurlNotCallable)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(AocCommandError::CurlBadExitStatus(status))
+    }
+}
+
+/// Finds the session cookie in the places aoc-cli looks: the `ADVENT_OF_CODE_SESSION` variable,
+/// `~/.adventofcode.session` and `adventofcode.session` in the config directory.
+pub fn session_cookie() -> Result<String, AocCommandError> {
+    if let Ok(session) = env::var("ADVENT_OF_CODE_SESSION") {
+        return Ok(session.trim().to_string());
+    }
+
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".config")));
+
+    let candidates = [
+        home.map(|home| home.join(".adventofcode.session")),
+        config_dir.map(|dir| dir.join("adventofcode.session")),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| {
+            AocCommandError::NoSession(
+                "set ADVENT_OF_CODE_SESSION or create ~/.adventofcode.session".into(),
+            )
+        })
+}
+
 fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+    config::get().input_path(day).display().to_string()
 }
 
 fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+    config::get().puzzle_path(day).display().to_string()
 }
 
 fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+    config::get().year
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
@@ -112,7 +192,12 @@ fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
 
 fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
-    let output = Command::new("aoc")
+    let mut cmd = Command::new("aoc");
+    // hand over the cookie, so that both aoc-cli and the leaderboard fetch use the same session.
+    if let Ok(session) = session_cookie() {
+        cmd.env("ADVENT_OF_CODE_SESSION", session);
+    }
+    let output = cmd
         .args(args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
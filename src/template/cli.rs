@@ -0,0 +1,358 @@
+/// The command-line model shared by the `advent_of_code` binary and the solution binaries.
+use std::{io, path::PathBuf};
+
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::template::config::{self, ColorChoice, Config};
+use crate::template::leaderboard::SortOrder;
+use crate::Day;
+
+/// Options accepted by every command, including the solution binaries.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalArgs {
+    /// Puzzle year, overrides `year` in aoc.toml.
+    #[arg(long, global = true, value_name = "YEAR")]
+    pub year: Option<u16>,
+    /// Directory holding inputs, examples and puzzles, overrides `data_dir` in aoc.toml.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Only print results and errors.
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+    /// When to style output.
+    #[arg(long, global = true, value_name = "WHEN")]
+    pub color: Option<ColorChoice>,
+}
+
+impl GlobalArgs {
+    /// Overrides configuration values with the options passed on the command-line.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(year) = self.year {
+            config.year = Some(year);
+        }
+        if let Some(data_dir) = &self.data_dir {
+            config.data_dir.clone_from(data_dir);
+        }
+        if self.quiet {
+            config.output.quiet = true;
+        }
+        if let Some(color) = self.color {
+            config.output.color = color;
+        }
+    }
+
+    /// Loads the configuration, applies these options and makes it the process configuration.
+    pub fn init_config(&self) -> &'static Config {
+        let mut config = Config::load().unwrap_or_else(|e| {
+            eprintln!("Failed to load configuration: {e}");
+            std::process::exit(1);
+        });
+        self.apply(&mut config);
+        config::init(config)
+    }
+
+    /// The options that reproduce `config` in a child process.
+    ///
+    /// The color choice is resolved, since the output of children is usually piped.
+    pub fn from_config(config: &Config) -> Self {
+        let color = if config.output.color.enabled() {
+            ColorChoice::Always
+        } else {
+            ColorChoice::Never
+        };
+
+        Self {
+            year: config.year,
+            data_dir: Some(config.data_dir.clone()),
+            quiet: config.output.quiet,
+            color: Some(color),
+        }
+    }
+
+    /// Renders these options as command-line arguments.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(year) = self.year {
+            args.push("--year".into());
+            args.push(year.to_string());
+        }
+        if let Some(data_dir) = &self.data_dir {
+            args.push("--data-dir".into());
+            args.push(data_dir.display().to_string());
+        }
+        if self.quiet {
+            args.push("--quiet".into());
+        }
+        if let Some(color) = self.color {
+            args.push("--color".into());
+            args.push(
+                match color {
+                    ColorChoice::Auto => "auto",
+                    ColorChoice::Always => "always",
+                    ColorChoice::Never => "never",
+                }
+                .into(),
+            );
+        }
+        args
+    }
+}
+
+/// Command-line helper for solving Advent of Code puzzles.
+#[derive(Parser, Debug)]
+#[command(name = "advent_of_code", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Create the solution module, input and example files for a day.
+    Scaffold {
+        /// Day number (1-25).
+        day: Day,
+    },
+    /// Download the input and puzzle description for a day via aoc-cli.
+    Download {
+        /// Day number (1-25).
+        day: Day,
+        /// Wait for the puzzle to unlock, then retry until the download succeeds.
+        #[arg(long)]
+        wait: bool,
+        /// Scaffold the day after downloading, unless its module exists already.
+        #[arg(long)]
+        scaffold: bool,
+    },
+    /// Print the puzzle description for a day via aoc-cli.
+    Read {
+        /// Day number (1-25).
+        day: Day,
+    },
+    /// Run the solution for a day against its puzzle input.
+    Solve {
+        /// Day number (1-25).
+        day: Day,
+        #[command(flatten)]
+        run: RunArgs,
+        /// Submit the answer of the given part via aoc-cli.
+        #[arg(long, value_name = "PART", value_parser = clap::value_parser!(u8).range(1..=2))]
+        submit: Option<u8>,
+    },
+    /// Run all solutions in sequence; `--release --time` also updates the readme benchmarks.
+    All {
+        #[command(flatten)]
+        run: RunArgs,
+    },
+    /// Show the progress of every day: files, answers and benchmarks.
+    Status {
+        /// Only inspect files, don't run the solutions.
+        #[arg(long)]
+        no_run: bool,
+        /// Build the solutions with optimizations.
+        #[arg(long)]
+        release: bool,
+    },
+    /// Show a private leaderboard from its cached JSON.
+    Leaderboard(LeaderboardArgs),
+    /// Print a completion script for the given shell.
+    Completions { shell: Shell },
+}
+
+/// Options controlling how solution binaries are built and run.
+#[derive(Args, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunArgs {
+    /// Build the solutions with optimizations.
+    #[arg(long)]
+    pub release: bool,
+    /// Benchmark each part.
+    #[arg(long)]
+    pub time: bool,
+}
+
+/// Options of the `leaderboard` command.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeaderboardArgs {
+    /// Leaderboard id, overrides `leaderboard.id` in aoc.toml.
+    pub id: Option<u64>,
+    /// Read the leaderboard from this JSON file instead of the cache.
+    #[arg(long, value_name = "FILE", conflicts_with = "fetch")]
+    pub file: Option<PathBuf>,
+    /// Download the leaderboard into the cache first (at most every 15 minutes).
+    #[arg(long)]
+    pub fetch: bool,
+    /// Show the completion times of a day instead of the overview.
+    #[arg(long)]
+    pub day: Option<Day>,
+    /// How to rank members.
+    #[arg(long, value_enum, default_value_t)]
+    pub sort: SortOrder,
+}
+
+/// Runs a single solution against its puzzle input.
+#[derive(Parser, Debug, Default, PartialEq, Eq)]
+#[command(version)]
+pub struct SolutionArgs {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    /// Benchmark each part.
+    #[arg(long)]
+    pub time: bool,
+    /// Submit the answer of the given part via aoc-cli.
+    #[arg(long, value_name = "PART", value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub submit: Option<u8>,
+}
+
+impl SolutionArgs {
+    /// Parses the arguments of the current process, exiting with usage on error.
+    pub fn from_env() -> Self {
+        Self::parse()
+    }
+
+    /// Renders these options as command-line arguments.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = self.global.to_args();
+        if self.time {
+            args.push("--time".into());
+        }
+        if let Some(part) = self.submit {
+            args.push("--submit".into());
+            args.push(part.to_string());
+        }
+        args
+    }
+}
+
+/// Writes a completion script for the `advent_of_code` binary to stdout.
+pub fn print_completions(shell: Shell) {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut io::stdout());
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use clap::{CommandFactory, Parser};
+
+    use super::{Cli, Command, GlobalArgs, LeaderboardArgs, RunArgs, SolutionArgs};
+    use crate::day;
+    use crate::template::config::{ColorChoice, Config};
+    use crate::template::leaderboard::SortOrder;
+
+    #[test]
+    fn command_model_is_consistent() {
+        Cli::command().debug_assert();
+        SolutionArgs::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_global_options_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "aoc",
+            "solve",
+            "3",
+            "--release",
+            "--year",
+            "2022",
+            "--submit",
+            "2",
+            "-q",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.command,
+            Command::Solve {
+                day: day!(3),
+                run: RunArgs {
+                    release: true,
+                    time: false
+                },
+                submit: Some(2),
+            }
+        );
+        assert_eq!(cli.global.year, Some(2022));
+        assert!(cli.global.quiet);
+    }
+
+    #[test]
+    fn parses_leaderboard_options() {
+        let cli =
+            Cli::try_parse_from(["aoc", "leaderboard", "42", "--day", "5", "--sort", "stars"])
+                .unwrap();
+
+        assert_eq!(
+            cli.command,
+            Command::Leaderboard(LeaderboardArgs {
+                id: Some(42),
+                day: Some(day!(5)),
+                sort: SortOrder::Stars,
+                ..Default::default()
+            })
+        );
+        assert!(Cli::try_parse_from(["aoc", "leaderboard", "--file", "x", "--fetch"]).is_err());
+    }
+
+    #[test]
+    fn parses_download_options() {
+        let cli = Cli::try_parse_from(["aoc", "download", "7", "--wait", "--scaffold"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Download {
+                day: day!(7),
+                wait: true,
+                scaffold: true
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(Cli::try_parse_from(["aoc", "solve", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "solve", "1", "--submit", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "all", "--unknown"]).is_err());
+        assert!(Cli::try_parse_from(["aoc"]).is_err());
+    }
+
+    #[test]
+    fn applies_global_options() {
+        let mut config = Config::default();
+        GlobalArgs {
+            year: Some(2021),
+            data_dir: Some(PathBuf::from("other")),
+            quiet: true,
+            color: Some(ColorChoice::Never),
+        }
+        .apply(&mut config);
+
+        assert_eq!(config.year, Some(2021));
+        assert_eq!(config.data_dir, PathBuf::from("other"));
+        assert!(config.output.quiet);
+        assert_eq!(config.output.color, ColorChoice::Never);
+    }
+
+    #[test]
+    fn solution_args_round_trip() {
+        let args = SolutionArgs {
+            global: GlobalArgs {
+                year: Some(2023),
+                data_dir: Some(PathBuf::from("data")),
+                quiet: false,
+                color: Some(ColorChoice::Always),
+            },
+            time: true,
+            submit: Some(1),
+        };
+
+        let rendered = args.to_args();
+        let parsed =
+            SolutionArgs::try_parse_from(std::iter::once("01".to_string()).chain(rendered))
+                .unwrap();
+        assert_eq!(parsed, args);
+    }
+}
@@ -1,12 +1,19 @@
 use std::io;
 
 use crate::template::{
+    ansi,
+    cli::RunArgs,
+    is_quiet,
     readme_benchmarks::{self, Timings},
     ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
 use crate::{all_days, Day};
 
-pub fn handle(is_release: bool, is_timed: bool) {
+pub fn handle(run: RunArgs) {
+    let RunArgs {
+        release: is_release,
+        time: is_timed,
+    } = run;
     let mut timings: Vec<Timings> = vec![];
 
     all_days().for_each(|day| {
@@ -14,13 +21,15 @@ pub fn handle(is_release: bool, is_timed: bool) {
             println!();
         }
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("{}Day {day}{}", ansi(ANSI_BOLD), ansi(ANSI_RESET));
         println!("------");
 
         let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
 
         if output.is_empty() {
-            println!("Not solved.");
+            if !is_quiet() {
+                println!("Not solved.");
+            }
         } else {
             let val = child_commands::parse_exec_time(&output, day);
             timings.push(val);
@@ -30,10 +39,17 @@ pub fn handle(is_release: bool, is_timed: bool) {
     if is_timed {
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+        println!(
+            "\n{}Total:{} {}{total_millis:.2}ms{}",
+            ansi(ANSI_BOLD),
+            ansi(ANSI_RESET),
+            ansi(ANSI_ITALIC),
+            ansi(ANSI_RESET)
+        );
 
         if is_release {
             match readme_benchmarks::update(timings, total_millis) {
+                Ok(()) if is_quiet() => {}
                 Ok(()) => println!("Successfully updated README with benchmarks."),
                 Err(_) => {
                     eprintln!("Failed to update readme with benchmarks.");
@@ -65,6 +81,8 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::template::cli::{GlobalArgs, SolutionArgs};
+    use crate::template::config;
     use crate::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -87,11 +105,15 @@ mod child_commands {
             args.push("--release");
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+        // mirror `--time` and the global options to child invocations.
+        let child_args = SolutionArgs {
+            global: GlobalArgs::from_config(config::get()),
+            time: is_timed,
+            submit: None,
         }
+        .to_args();
+        args.push("--");
+        args.extend(child_args.iter().map(String::as_str));
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
@@ -171,7 +193,7 @@ mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -1,15 +1,70 @@
-use crate::template::aoc_cli;
+use crate::template::commands::scaffold;
+use crate::template::release::{
+    format_duration, unlock_time, wait_until, Backoff, Clock, SystemClock,
+};
+use crate::template::{aoc_cli, config, is_quiet};
 use crate::Day;
+use chrono::Datelike;
+use std::io::{self, Write};
+use std::path::Path;
 use std::process;
 
-pub fn handle(day: Day) {
+pub fn handle(day: Day, wait: bool, scaffold: bool) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
+    let result = if wait {
+        let mut clock = SystemClock;
+        wait_for_unlock(&mut clock, day);
+        Backoff::default().retry(
+            &mut clock,
+            || aoc_cli::download(day),
+            |e, delay| eprintln!("{e} Retrying in {}s...", delay.as_secs()),
+        )
+    } else {
+        aoc_cli::download(day)
+    };
+
+    if let Err(e) = result {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    // re-running a download for a day that is already being worked on keeps its module.
+    if scaffold {
+        let module_path = scaffold::module_path(day);
+        if Path::new(&module_path).exists() {
+            if !is_quiet() {
+                println!("Module \"{module_path}\" exists already, not scaffolding.");
+            }
+        } else {
+            scaffold::handle(day);
+        }
+    }
+}
+
+/// Shows a countdown until the puzzle for `day` of the configured year unlocks.
+fn wait_for_unlock(clock: &mut impl Clock, day: Day) {
+    let year = config::get()
+        .year
+        .map_or_else(|| clock.now().year(), i32::from);
+    let unlock = unlock_time(year, day);
+
+    let mut waited = false;
+    wait_until(clock, unlock, |remaining| {
+        waited = true;
+        if !is_quiet() {
+            print!(
+                "\r⏳ Day {day} of {year} unlocks in {}",
+                format_duration(remaining)
+            );
+            let _ = io::stdout().flush();
+        }
+    });
+
+    if waited && !is_quiet() {
+        println!("\r🔓 Day {day} of {year} is unlocked!          ");
+    }
 }
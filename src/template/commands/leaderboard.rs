@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{aoc_cli, cli::LeaderboardArgs, config, is_quiet, leaderboard::Leaderboard};
+
+/// The leaderboard API asks clients not to request a leaderboard more often than this.
+const MIN_FETCH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+pub fn handle(options: LeaderboardArgs) {
+    let path = match &options.file {
+        Some(file) => file.clone(),
+        None => cache_path(options.id),
+    };
+
+    if options.fetch {
+        if let Err(e) = fetch(&path, options.id) {
+            eprintln!("Failed to fetch leaderboard: {e}");
+            process::exit(1);
+        }
+    }
+
+    let leaderboard = match fs::read_to_string(&path) {
+        Ok(s) => s.parse::<Leaderboard>(),
+        Err(e) => {
+            eprintln!("Failed to read \"{}\": {e}", path.display());
+            if options.file.is_none() && !options.fetch {
+                eprintln!("Run the command with `--fetch` to download the leaderboard.");
+            }
+            process::exit(1);
+        }
+    };
+
+    let rendered = leaderboard.and_then(|lb| match options.day {
+        Some(day) => lb.render_day(day, options.sort),
+        None => Ok(lb.render_overview(options.sort)),
+    });
+
+    match rendered {
+        Ok(table) => println!("{table}"),
+        Err(e) => {
+            eprintln!("Failed to read \"{}\": {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+fn year_and_id(id: Option<u64>) -> (u16, u64) {
+    let config = config::get();
+    let Some(year) = config.year else {
+        eprintln!("No year configured. Set `year` in aoc.toml or pass `--year`.");
+        process::exit(1);
+    };
+    let Some(id) = id.or(config.leaderboard.id) else {
+        eprintln!(
+            "No leaderboard id given. Pass it as argument or set `leaderboard.id` in aoc.toml."
+        );
+        process::exit(1);
+    };
+    (year, id)
+}
+
+fn cache_path(id: Option<u64>) -> PathBuf {
+    let (year, id) = year_and_id(id);
+    config::get().leaderboard_path(year, id)
+}
+
+fn is_recent(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < MIN_FETCH_INTERVAL)
+}
+
+/// Downloads the leaderboard JSON to `path`. The download only replaces `path` once it parses,
+/// so an error page never ends up in the cache.
+fn fetch(path: &Path, id: Option<u64>) -> Result<(), String> {
+    if is_recent(path) {
+        if !is_quiet() {
+            println!(
+                "Using \"{}\", it was fetched less than 15 minutes ago.",
+                path.display()
+            );
+        }
+        return Ok(());
+    }
+
+    let (year, id) = year_and_id(id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut download = path.as_os_str().to_owned();
+    download.push(".part");
+    let download = PathBuf::from(download);
+
+    if let Err(e) = aoc_cli::fetch_private_leaderboard(year, id, &download) {
+        let _ = fs::remove_file(&download);
+        return Err(e.to_string());
+    }
+
+    // an expired session gets a login page instead of the JSON.
+    let parsed = fs::read_to_string(&download)
+        .map_err(|e| e.to_string())
+        .and_then(|s| s.parse::<Leaderboard>().map_err(|e| e.to_string()));
+    if let Err(e) = parsed {
+        let _ = fs::remove_file(&download);
+        return Err(format!(
+            "the response is not a leaderboard, is the session cookie still valid? ({e})"
+        ));
+    }
+    fs::rename(&download, path).map_err(|e| e.to_string())?;
+
+    if !is_quiet() {
+        println!(
+            "🎄 Successfully wrote leaderboard to \"{}\".",
+            path.display()
+        );
+    }
+    Ok(())
+}
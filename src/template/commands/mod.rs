@@ -1,5 +1,7 @@
 pub mod all;
 pub mod download;
+pub mod leaderboard;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
@@ -4,6 +4,7 @@ use std::{
     process,
 };
 
+use crate::template::{config, is_quiet};
 use crate::Day;
 
 const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
@@ -39,13 +40,23 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// Path of the solution module of a day.
+pub fn module_path(day: Day) -> String {
+    format!("src/bin/{day}.rs")
 }
 
 pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+    let config = config::get();
+    let input_path = config.input_path(day).display().to_string();
+    let example_path = config.example_path(day).display().to_string();
+    let module_path = module_path(day);
 
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
@@ -60,6 +71,7 @@ pub fn handle(day: Day) {
             .replace("DAY_NUMBER", &day.into_inner().to_string())
             .as_bytes(),
     ) {
+        Ok(()) if is_quiet() => {}
         Ok(()) => {
             println!("Created module file \"{}\"", &module_path);
         }
@@ -70,6 +82,7 @@ pub fn handle(day: Day) {
     }
 
     match create_file(&input_path) {
+        Ok(_) if is_quiet() => {}
         Ok(_) => {
             println!("Created empty input file \"{}\"", &input_path);
         }
@@ -80,6 +93,7 @@ pub fn handle(day: Day) {
     }
 
     match create_file(&example_path) {
+        Ok(_) if is_quiet() => {}
         Ok(_) => {
             println!("Created empty example file \"{}\"", &example_path);
         }
@@ -89,6 +103,8 @@ pub fn handle(day: Day) {
         }
     }
 
-    println!("---");
-    println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    if !is_quiet() {
+        println!("---");
+        println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    }
 }
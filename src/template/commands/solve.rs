@@ -1,24 +1,24 @@
 use std::process::{Command, Stdio};
 
+use crate::template::cli::{GlobalArgs, RunArgs, SolutionArgs};
+use crate::template::config;
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+pub fn handle(day: Day, run: RunArgs, submit_part: Option<u8>) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
-    if release {
+    if run.release {
         cmd_args.push("--release".to_string());
     }
 
     cmd_args.push("--".to_string());
 
-    if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
-    }
-
-    if time {
-        cmd_args.push("--time".to_string());
-    }
+    let child_args = SolutionArgs {
+        global: GlobalArgs::from_config(config::get()),
+        time: run.time,
+        submit: submit_part,
+    };
+    cmd_args.extend(child_args.to_args());
 
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
@@ -0,0 +1,83 @@
+use std::{fs, process::Command};
+
+use crate::template::cli::{GlobalArgs, SolutionArgs};
+use crate::template::config::{self, ColorChoice};
+use crate::template::readme_benchmarks;
+use crate::template::status::{self, DayStatus, FileState, PartOutput, PartState};
+use crate::{all_days, Day};
+
+pub fn handle(run: bool, is_release: bool) {
+    let config = config::get();
+    let timings = readme_benchmarks::read().unwrap_or_default();
+
+    let statuses: Vec<DayStatus> = all_days()
+        .map(|day| {
+            let timing = timings.iter().find(|t| t.day == day);
+            let mut status = DayStatus {
+                day,
+                bin: FileState::of(readme_benchmarks::get_path_for_bin(day)),
+                input: FileState::of(config.input_path(day)),
+                example: FileState::of(config.example_path(day)),
+                puzzle: FileState::of(config.puzzle_path(day)),
+                parts: [PartState::Unknown, PartState::Unknown],
+                benchmarks: [
+                    timing.and_then(|t| t.part_1.clone()),
+                    timing.and_then(|t| t.part_2.clone()),
+                ],
+            };
+
+            if run && status.is_runnable() {
+                if let Some(outputs) = run_solution(day, is_release) {
+                    let answers = fs::read_to_string(config.puzzle_path(day))
+                        .map(|puzzle| status::parse_answers(&puzzle))
+                        .unwrap_or_default();
+                    let [part_1, part_2] = outputs;
+                    status.parts = [
+                        PartState::check(part_1, answers.first().map(String::as_str)),
+                        PartState::check(part_2, answers.get(1).map(String::as_str)),
+                    ];
+                }
+            }
+
+            status
+        })
+        .collect();
+
+    println!("{}", status::render(&statuses));
+}
+
+/// Runs the solution bin for a given day and collects the result of each part.
+/// Returns `None` if the solution failed to build or run.
+fn run_solution(day: Day, is_release: bool) -> Option<[PartOutput; 2]> {
+    let day_padded = day.to_string();
+    let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+
+    if is_release {
+        args.push("--release");
+    }
+
+    // the output is parsed, so it must not be styled.
+    let child_args = SolutionArgs {
+        global: GlobalArgs {
+            quiet: true,
+            color: Some(ColorChoice::Never),
+            ..GlobalArgs::from_config(config::get())
+        },
+        time: false,
+        submit: None,
+    }
+    .to_args();
+    args.push("--");
+    args.extend(child_args.iter().map(String::as_str));
+
+    let output = Command::new("cargo").args(&args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect();
+    Some(status::parse_outputs(&lines))
+}
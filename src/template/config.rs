@@ -0,0 +1,464 @@
+/// Project configuration, read from `aoc.toml` in the project root.
+///
+/// Every setting has a default, so the file (and any of its sections) is optional.
+/// Environment variables take precedence over values read from the file:
+///
+/// | Variable                | Setting             |
+/// | ----------------------- | ------------------- |
+/// | `AOC_CONFIG`            | path of the file    |
+/// | `AOC_YEAR`              | `year`              |
+/// | `AOC_DATA_DIR`          | `data_dir`          |
+/// | `AOC_README`            | `readme.path`       |
+/// | `AOC_README_MARKER`     | `readme.marker`     |
+/// | `AOC_BENCH_BUDGET_MS`   | `bench.budget_ms`   |
+/// | `AOC_BENCH_MIN_SAMPLES` | `bench.min_samples` |
+/// | `AOC_BENCH_MAX_SAMPLES` | `bench.max_samples` |
+/// | `AOC_SUBMIT`            | `submit.policy`     |
+/// | `AOC_LEADERBOARD_ID`    | `leaderboard.id`    |
+///
+/// Command-line options such as `--year` are applied on top, see [`crate::template::cli`].
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::template::readme_benchmarks;
+use crate::Day;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The puzzle year passed to aoc-cli. Defaults to aoc-cli's own choice.
+    pub year: Option<u16>,
+    /// Directory holding the `inputs`, `examples` and `puzzles` folders.
+    pub data_dir: PathBuf,
+    pub readme: ReadmeConfig,
+    pub bench: BenchConfig,
+    pub submit: SubmitConfig,
+    pub output: OutputConfig,
+    pub leaderboard: LeaderboardConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadmeConfig {
+    /// The readme that receives the benchmark table.
+    pub path: PathBuf,
+    /// The marker enclosing the benchmark table.
+    pub marker: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchConfig {
+    /// Approximate wall time spent benchmarking a single part.
+    pub budget_ms: u64,
+    pub min_samples: u32,
+    pub max_samples: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmitConfig {
+    pub policy: SubmitPolicy,
+}
+
+/// Controls when `--submit` is allowed to send an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmitPolicy {
+    /// Submit whenever `--submit` is passed.
+    #[default]
+    Always,
+    /// Submit only from optimized builds.
+    Release,
+    /// Never submit.
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Suppress informational messages.
+    pub quiet: bool,
+    pub color: ColorChoice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LeaderboardConfig {
+    /// The private leaderboard shown by default, i.e. the owner's user id.
+    pub id: Option<u64>,
+}
+
+/// Controls whether output is styled with ANSI escape codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Style output if stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves [`ColorChoice::Auto`] for the current process.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            data_dir: PathBuf::from("data"),
+            readme: ReadmeConfig::default(),
+            bench: BenchConfig::default(),
+            submit: SubmitConfig::default(),
+            output: OutputConfig::default(),
+            leaderboard: LeaderboardConfig::default(),
+        }
+    }
+}
+
+impl Default for ReadmeConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("README.md"),
+            marker: readme_benchmarks::MARKER.into(),
+        }
+    }
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            budget_ms: 1000,
+            min_samples: 10,
+            max_samples: 10000,
+        }
+    }
+}
+
+impl FromStr for SubmitPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(SubmitPolicy::Always),
+            "release" => Ok(SubmitPolicy::Release),
+            "never" => Ok(SubmitPolicy::Never),
+            _ => Err(Error::InvalidValue {
+                key: "AOC_SUBMIT",
+                value: s.into(),
+            }),
+        }
+    }
+}
+
+impl SubmitPolicy {
+    /// Whether a submission is allowed from the current build.
+    pub fn allows_submit(self) -> bool {
+        match self {
+            SubmitPolicy::Always => true,
+            SubmitPolicy::Release => !cfg!(debug_assertions),
+            SubmitPolicy::Never => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IO(PathBuf, io::Error),
+    Parser(PathBuf, toml::de::Error),
+    InvalidValue {
+        key: &'static str,
+        value: String,
+    },
+    /// `bench.min_samples` is larger than `bench.max_samples`.
+    SampleRange {
+        min: u32,
+        max: u32,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::IO(path, e) => write!(f, "could not read {}: {e}", path.display()),
+            Error::Parser(path, e) => write!(f, "could not parse {}: {e}", path.display()),
+            Error::InvalidValue { key, value } => write!(f, "invalid value for {key}: {value:?}"),
+            Error::SampleRange { min, max } => write!(
+                f,
+                "bench.min_samples ({min}) must not be larger than bench.max_samples ({max})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Config {
+    /// Parses a configuration from the contents of an `aoc.toml` file.
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Reads the configuration file (if present) and applies environment overrides.
+    pub fn load() -> Result<Self, Error> {
+        let path =
+            env::var_os("AOC_CONFIG").map_or_else(|| PathBuf::from(CONFIG_FILE), PathBuf::from);
+        let mut config = Self::load_file(&path)?;
+        config.apply_overrides(|key| env::var(key).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the settings that are valid on their own but not together.
+    pub fn validate(&self) -> Result<(), Error> {
+        let BenchConfig {
+            min_samples: min,
+            max_samples: max,
+            ..
+        } = self.bench;
+        if min > max {
+            return Err(Error::SampleRange { min, max });
+        }
+        Ok(())
+    }
+
+    fn load_file(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::from_toml_str(&s).map_err(|e| Error::Parser(path.into(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::IO(path.into(), e)),
+        }
+    }
+
+    /// Overrides settings with the values returned by `lookup` for each environment variable.
+    pub fn apply_overrides(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), Error> {
+        fn parse<T: FromStr>(key: &'static str, value: String) -> Result<T, Error> {
+            value
+                .parse()
+                .map_err(|_| Error::InvalidValue { key, value })
+        }
+
+        if let Some(year) = lookup("AOC_YEAR") {
+            self.year = Some(parse("AOC_YEAR", year)?);
+        }
+        if let Some(dir) = lookup("AOC_DATA_DIR") {
+            self.data_dir = dir.into();
+        }
+        if let Some(path) = lookup("AOC_README") {
+            self.readme.path = path.into();
+        }
+        if let Some(marker) = lookup("AOC_README_MARKER") {
+            self.readme.marker = marker;
+        }
+        if let Some(budget) = lookup("AOC_BENCH_BUDGET_MS") {
+            self.bench.budget_ms = parse("AOC_BENCH_BUDGET_MS", budget)?;
+        }
+        if let Some(min) = lookup("AOC_BENCH_MIN_SAMPLES") {
+            self.bench.min_samples = parse("AOC_BENCH_MIN_SAMPLES", min)?;
+        }
+        if let Some(max) = lookup("AOC_BENCH_MAX_SAMPLES") {
+            self.bench.max_samples = parse("AOC_BENCH_MAX_SAMPLES", max)?;
+        }
+        if let Some(policy) = lookup("AOC_SUBMIT") {
+            self.submit.policy = policy.parse()?;
+        }
+        if let Some(id) = lookup("AOC_LEADERBOARD_ID") {
+            self.leaderboard.id = Some(parse("AOC_LEADERBOARD_ID", id)?);
+        }
+        Ok(())
+    }
+
+    /// Path of a data file, e.g. `data/inputs/01.txt`.
+    pub fn data_path(&self, folder: &str, file_name: &str) -> PathBuf {
+        self.data_dir.join(folder).join(file_name)
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.data_path("inputs", &format!("{day}.txt"))
+    }
+
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.data_path("examples", &format!("{day}.txt"))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.data_path("puzzles", &format!("{day}.md"))
+    }
+
+    /// Path of the cached JSON for a private leaderboard, e.g. `data/leaderboards/2023-123456.json`.
+    pub fn leaderboard_path(&self, year: u16, id: u64) -> PathBuf {
+        self.data_path("leaderboards", &format!("{year}-{id}.json"))
+    }
+}
+
+impl BenchConfig {
+    pub fn budget(&self) -> Duration {
+        Duration::from_millis(self.budget_ms)
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Sets the configuration of the current process, e.g. after applying command-line options.
+///
+/// # Panics
+/// Panics if the configuration was already initialized or read.
+pub fn init(config: Config) -> &'static Config {
+    if CONFIG.set(config).is_err() {
+        panic!("configuration initialized twice");
+    }
+    get()
+}
+
+/// The configuration of the current process, loaded on first use.
+///
+/// Exits the process if the configuration is invalid.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        Config::load().unwrap_or_else(|e| {
+            eprintln!("Failed to load configuration: {e}");
+            std::process::exit(1);
+        })
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{ColorChoice, Config, SubmitPolicy};
+    use crate::day;
+
+    #[test]
+    fn empty_file_uses_defaults() {
+        let config = Config::from_toml_str("").unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(
+            config.input_path(day!(1)),
+            PathBuf::from("data/inputs/01.txt")
+        );
+    }
+
+    #[test]
+    fn parses_all_sections() {
+        let config = Config::from_toml_str(
+            r#"
+            year = 2022
+            data_dir = "aoc-data"
+
+            [readme]
+            path = "docs/README.md"
+            marker = "<!-- bench -->"
+
+            [bench]
+            budget_ms = 250
+            min_samples = 5
+
+            [submit]
+            policy = "never"
+
+            [output]
+            color = "never"
+
+            [leaderboard]
+            id = 1234
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(
+            config.puzzle_path(day!(3)),
+            PathBuf::from("aoc-data/puzzles/03.md")
+        );
+        assert_eq!(config.readme.path, PathBuf::from("docs/README.md"));
+        assert_eq!(config.readme.marker, "<!-- bench -->");
+        assert_eq!(config.bench.budget_ms, 250);
+        assert_eq!(config.bench.min_samples, 5);
+        assert_eq!(config.bench.max_samples, 10000);
+        assert_eq!(config.submit.policy, SubmitPolicy::Never);
+        assert_eq!(config.output.color, ColorChoice::Never);
+        assert!(!config.output.quiet);
+        assert_eq!(config.leaderboard.id, Some(1234));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(Config::from_toml_str("yaer = 2022").is_err());
+    }
+
+    #[test]
+    fn rejects_inverted_sample_range() {
+        let config = Config::from_toml_str("[bench]\nmin_samples = 50\nmax_samples = 20").unwrap();
+        let e = config.validate().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "bench.min_samples (50) must not be larger than bench.max_samples (20)"
+        );
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let mut config = Config::from_toml_str("year = 2022\ndata_dir = \"foo\"").unwrap();
+        config
+            .apply_overrides(|key| match key {
+                "AOC_YEAR" => Some("2023".into()),
+                "AOC_SUBMIT" => Some("release".into()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.data_dir, PathBuf::from("foo"));
+        assert_eq!(config.submit.policy, SubmitPolicy::Release);
+    }
+
+    #[test]
+    fn env_overrides_sample_range() {
+        let mut config = Config::from_toml_str("[bench]\nmin_samples = 5").unwrap();
+        config
+            .apply_overrides(|key| match key {
+                "AOC_BENCH_MIN_SAMPLES" => Some("20".into()),
+                "AOC_BENCH_MAX_SAMPLES" => Some("15".into()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.bench.min_samples, 20);
+        assert_eq!(config.bench.max_samples, 15);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_overrides() {
+        let mut config = Config::default();
+        assert!(config
+            .apply_overrides(|key| (key == "AOC_YEAR").then(|| "twenty".into()))
+            .is_err());
+    }
+}
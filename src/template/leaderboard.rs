@@ -0,0 +1,295 @@
+/// Model and rendering of Advent of Code private leaderboards.
+///
+/// The data is the JSON served at `https://adventofcode.com/{year}/leaderboard/private/view/{id}.json`.
+use std::{cmp::Reverse, collections::HashMap, fmt::Display, str::FromStr};
+
+use chrono::Duration;
+use serde::Deserialize;
+
+use crate::template::release::{format_duration, unlock_time};
+use crate::template::render_table;
+use crate::{all_days, Day};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    pub last_star_ts: i64,
+    /// Stars by day, then by part, both keyed by their number.
+    #[serde(default)]
+    pub completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+/// The order of members in a rendered leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SortOrder {
+    /// By local score, like the leaderboard page.
+    #[default]
+    Local,
+    /// By number of stars.
+    Stars,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(serde_json::Error),
+    InvalidEvent(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "could not parse leaderboard: {e}"),
+            Error::InvalidEvent(event) => write!(f, "unexpected leaderboard event {event:?}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl FromStr for Leaderboard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let leaderboard: Leaderboard = serde_json::from_str(s).map_err(Error::Parser)?;
+        leaderboard.year()?;
+        Ok(leaderboard)
+    }
+}
+
+impl Leaderboard {
+    pub fn year(&self) -> Result<i32, Error> {
+        self.event
+            .parse()
+            .map_err(|_| Error::InvalidEvent(self.event.clone()))
+    }
+
+    /// Members ranked by `order`. Ties go to whoever got their last star first.
+    pub fn ranked(&self, order: SortOrder) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by_key(|m| {
+            let primary = match order {
+                SortOrder::Local => (m.local_score, m.stars),
+                SortOrder::Stars => (m.stars, m.local_score),
+            };
+            (Reverse(primary), m.last_star_ts, m.id)
+        });
+        members
+    }
+
+    /// Renders the ranked members with their stars for every day.
+    pub fn render_overview(&self, order: SortOrder) -> String {
+        let ruler = day_ruler();
+        let rows: Vec<Vec<String>> = self
+            .ranked(order)
+            .into_iter()
+            .enumerate()
+            .map(|(rank, m)| {
+                let days: String = all_days()
+                    .map(|day| match (m.star(day, 1), m.star(day, 2)) {
+                        (Some(_), Some(_)) => '*',
+                        (Some(_), None) => '+',
+                        _ => '.',
+                    })
+                    .collect();
+                vec![
+                    format!("{})", rank + 1),
+                    m.local_score.to_string(),
+                    m.stars.to_string(),
+                    // as wide as the ruler, so that the days line up with their labels.
+                    format!("{days:<w$}", w = ruler.len()),
+                    m.display_name(),
+                ]
+            })
+            .collect();
+
+        let header = ["", "Score", "Stars", &ruler, "Name"];
+        render_table(&header, &rows)
+    }
+
+    /// Renders when each member got the stars of `day`, counted from the puzzle unlock.
+    pub fn render_day(&self, day: Day, order: SortOrder) -> Result<String, Error> {
+        let unlock = unlock_time(self.year()?, day);
+        let elapsed = |ts: Option<i64>| {
+            ts.map_or_else(
+                || "-".into(),
+                |ts| format_duration(Duration::seconds(ts - unlock.timestamp())),
+            )
+        };
+
+        let rows: Vec<Vec<String>> = self
+            .ranked(order)
+            .into_iter()
+            .filter(|m| m.star(day, 1).is_some())
+            .enumerate()
+            .map(|(rank, m)| {
+                vec![
+                    format!("{})", rank + 1),
+                    elapsed(m.star(day, 1)),
+                    elapsed(m.star(day, 2)),
+                    m.part_delta(day)
+                        .map_or_else(|| "-".into(), format_duration),
+                    m.display_name(),
+                ]
+            })
+            .collect();
+
+        let header = ["", "Part 1", "Part 2", "Delta", "Name"];
+        Ok(render_table(&header, &rows))
+    }
+}
+
+/// Labels for some days, each starting above the star column of its day.
+fn day_ruler() -> String {
+    let mut ruler = String::new();
+    for day in [1, 5, 10, 15, 20, 25] {
+        let column = day - 1;
+        ruler.extend(std::iter::repeat_n(' ', column - ruler.len()));
+        ruler.push_str(&day.to_string());
+    }
+    ruler
+}
+
+impl Member {
+    /// The member's name, or the placeholder the leaderboard page shows for anonymous users.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// The timestamp of the star for one part of a day, if obtained.
+    pub fn star(&self, day: Day, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.into_inner().to_string())?
+            .get(&part.to_string())
+            .map(|s| s.get_star_ts)
+    }
+
+    /// The time between the first and the second star of a day.
+    pub fn part_delta(&self, day: Day) -> Option<Duration> {
+        Some(Duration::seconds(self.star(day, 2)? - self.star(day, 1)?))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use chrono::Duration;
+
+    use super::{Leaderboard, SortOrder};
+    use crate::day;
+
+    // day 1 unlocks at 1701406800 (2023-12-01T05:00:00Z).
+    const JSON: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "stars": 3, "local_score": 10, "global_score": 0,
+                "last_star_ts": 1701500000,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1701407400, "star_index": 1 },
+                        "2": { "get_star_ts": 1701408000, "star_index": 2 }
+                    },
+                    "2": { "1": { "get_star_ts": 1701500000, "star_index": 3 } }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 4, "local_score": 8, "global_score": 0,
+                "last_star_ts": 1701600000,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1701406900, "star_index": 4 },
+                        "2": { "get_star_ts": 1701410500, "star_index": 5 }
+                    },
+                    "2": {
+                        "1": { "get_star_ts": 1701550000, "star_index": 6 },
+                        "2": { "get_star_ts": 1701600000, "star_index": 7 }
+                    }
+                }
+            },
+            "3": {
+                "id": 3, "name": "carol", "stars": 0, "local_score": 0, "global_score": 0,
+                "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    fn ranked_ids(lb: &Leaderboard, order: SortOrder) -> Vec<u64> {
+        lb.ranked(order).into_iter().map(|m| m.id).collect()
+    }
+
+    #[test]
+    fn ranks_by_local_score_or_stars() {
+        let lb: Leaderboard = JSON.parse().unwrap();
+        assert_eq!(ranked_ids(&lb, SortOrder::Local), vec![1, 2, 3]);
+        assert_eq!(ranked_ids(&lb, SortOrder::Stars), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn computes_part_deltas() {
+        let lb: Leaderboard = JSON.parse().unwrap();
+        let alice = &lb.members["1"];
+        assert_eq!(alice.part_delta(day!(1)), Some(Duration::seconds(600)));
+        assert_eq!(alice.part_delta(day!(2)), None);
+        assert_eq!(alice.star(day!(3), 1), None);
+    }
+
+    #[test]
+    fn renders_overview() {
+        let lb: Leaderboard = JSON.parse().unwrap();
+        let lines: Vec<_> = lb
+            .render_overview(SortOrder::Local)
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("1)     10      3  *+......"));
+        assert!(lines[1].ends_with("alice"));
+        assert!(lines[2].ends_with("(anonymous user #2)"));
+
+        // every label starts above the stars of its day.
+        let first = lines[1].find("*+").unwrap();
+        for day in [1, 5, 10, 15, 20, 25] {
+            let column = first + day - 1;
+            assert!(lines[0][column..].starts_with(&day.to_string()), "{day}");
+        }
+    }
+
+    #[test]
+    fn renders_day() {
+        let lb: Leaderboard = JSON.parse().unwrap();
+        let table = lb.render_day(day!(1), SortOrder::Stars).unwrap();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "1)  00:01:40  01:01:40  01:00:00  (anonymous user #2)"
+        );
+        assert_eq!(lines[2], "2)  00:10:00  00:20:00  00:10:00  alice");
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        assert!("{}".parse::<Leaderboard>().is_err());
+        assert!(JSON
+            .replace("\"2023\"", "\"twenty\"")
+            .parse::<Leaderboard>()
+            .is_err());
+    }
+}
@@ -1,20 +1,38 @@
 use crate::Day;
-use std::{env, fs};
+use std::fs;
 
 pub mod aoc_cli;
+pub mod cli;
 pub mod commands;
+pub mod config;
+pub mod leaderboard;
 pub mod readme_benchmarks;
+pub mod release;
 pub mod runner;
+pub mod status;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// Returns `code` if styled output is enabled, an empty string otherwise.
+pub fn ansi(code: &'static str) -> &'static str {
+    if config::get().output.color.enabled() {
+        code
+    } else {
+        ""
+    }
+}
+
+/// Whether informational messages should be suppressed.
+pub fn is_quiet() -> bool {
+    config::get().output.quiet
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = config::get().data_path(folder, &format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -22,15 +40,47 @@ pub fn read_file(folder: &str, day: Day) -> String {
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
+    let filepath = config::get().data_path(folder, &format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
+/// Renders rows as aligned columns below a header. The last column is left-aligned, all others
+/// are right-aligned.
+pub(crate) fn render_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|c| {
+            rows.iter()
+                .map(|r| r[c].chars().count())
+                .chain(std::iter::once(header[c].chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |cells: Vec<&str>| {
+        let last = cells.len() - 1;
+        cells
+            .into_iter()
+            .enumerate()
+            .map(|(c, cell)| match c {
+                // left-align the trailing name column, right-align everything else.
+                c if c == last => cell.to_string(),
+                c => format!("{cell:>w$}", w = widths[c]),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    std::iter::once(format_row(header.to_vec()))
+        .chain(
+            rows.iter()
+                .map(|r| format_row(r.iter().map(String::as_str).collect())),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 #[macro_export]
 macro_rules! solution {
@@ -40,9 +90,11 @@ macro_rules! solution {
 
         fn main() {
             use advent_of_code::template::runner::*;
+            let args = advent_of_code::template::cli::SolutionArgs::from_env();
+            args.global.init_config();
             let input = advent_of_code::template::read_file("inputs", DAY);
-            run_part(part_one, &input, DAY, 1);
-            run_part(part_two, &input, DAY, 2);
+            run_part(part_one, &input, DAY, 1, &args);
+            run_part(part_two, &input, DAY, 2, &args);
         }
     };
 }
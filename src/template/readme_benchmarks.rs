@@ -2,9 +2,11 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::config;
 use crate::Day;
 
-static MARKER: &str = "<!--- benchmarking table --->";
+/// The default marker enclosing the benchmark table, see [`config::ReadmeConfig`].
+pub static MARKER: &str = "<!--- benchmarking table --->";
 
 #[derive(Debug)]
 pub enum Error {
@@ -36,8 +38,8 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -58,11 +60,11 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
+fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64, marker: &str) -> String {
     let header = format!("{prefix} Benchmarks");
 
     let mut lines: Vec<String> = vec![
-        MARKER.into(),
+        marker.into(),
         header,
         String::new(),
         "| Day | Part 1 | Part 2 |".into(),
@@ -82,29 +84,68 @@ fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> St
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    lines.push(marker.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+fn update_content(
+    s: &mut String,
+    timings: Vec<Timings>,
+    total_millis: f64,
+    marker: &str,
+) -> Result<(), Error> {
+    let positions = locate_table(s, marker)?;
+    let table = construct_table("##", timings, total_millis, marker);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
 pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let path = "README.md";
+    let readme_config = &config::get().readme;
+    let path = &readme_config.path;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    update_content(&mut readme, timings, total_millis)?;
+    update_content(&mut readme, timings, total_millis, &readme_config.marker)?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
+/// Parses the timings of a previously written table. The table holds no per-day totals, so
+/// `total_nanos` is always zero.
+fn parse_table(readme: &str, marker: &str) -> Result<Vec<Timings>, Error> {
+    let positions = locate_table(readme, marker)?;
+    let table = &readme[positions.pos_start..positions.pos_end];
+
+    let parse_cell = |cell: &str| match cell.trim().trim_matches('`') {
+        "-" | "" => None,
+        s => Some(s.to_string()),
+    };
+
+    Ok(table
+        .lines()
+        .filter_map(|line| {
+            let cells: Vec<_> = line.strip_prefix("| [Day ")?.split('|').collect();
+            let day = cells.first()?.split(']').next()?.parse().ok()?;
+            Some(Timings {
+                day,
+                part_1: parse_cell(cells.get(1)?),
+                part_2: parse_cell(cells.get(2)?),
+                total_nanos: 0_f64,
+            })
+        })
+        .collect())
+}
+
+/// Reads the timings from the benchmark table of the configured readme.
+pub fn read() -> Result<Vec<Timings>, Error> {
+    let readme_config = &config::get().readme;
+    let readme = String::from_utf8_lossy(&fs::read(&readme_config.path)?).to_string();
+    parse_table(&readme, &readme_config.marker)
+}
+
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, Timings, MARKER};
+    use super::{parse_table, update_content, Timings, MARKER};
     use crate::day;
 
     fn get_mock_timings() -> Vec<Timings> {
@@ -134,28 +175,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -163,7 +204,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, get_mock_timings(), 190.0, MARKER).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -183,4 +224,19 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn reads_written_benchmarks() {
+        let mut s = format!("foo\n{}{}\nbaz", MARKER, MARKER);
+        let mut timings = get_mock_timings();
+        timings[1].part_2 = None;
+        update_content(&mut s, timings, 190.0, MARKER).unwrap();
+
+        let read = parse_table(&s, MARKER).unwrap();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[1].day, day!(2));
+        assert_eq!(read[1].part_1.as_deref(), Some("30ms"));
+        assert_eq!(read[1].part_2, None);
+        assert_eq!(read[2].day, day!(4));
+    }
 }
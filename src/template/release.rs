@@ -0,0 +1,242 @@
+/// Puzzle release times, and waiting for a puzzle to unlock.
+///
+/// Time is read and spent through the [`Clock`] trait, so that waiting can be simulated in tests.
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use crate::Day;
+
+/// A source of the current time that can also wait.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&mut self, duration: StdDuration);
+}
+
+/// The wall clock of the machine.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&mut self, duration: StdDuration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// The instant a puzzle unlocks: midnight US Eastern (UTC-5) on the given day of December.
+pub fn unlock_time(year: i32, day: Day) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, 12, day.into_inner().into(), 5, 0, 0)
+        .unwrap()
+}
+
+/// Formats a duration as `HH:MM:SS`, prefixed with the number of days if longer than one. Partial
+/// seconds round up, so a countdown only shows `00:00:00` once the time is up.
+pub fn format_duration(d: Duration) -> String {
+    let secs = (d.num_milliseconds() + 999).div_euclid(1000);
+    let (days, rem) = (secs / 86400, secs % 86400);
+    let hms = format!("{:02}:{:02}:{:02}", rem / 3600, rem % 3600 / 60, rem % 60);
+    if days > 0 {
+        format!("{days}d {hms}")
+    } else {
+        hms
+    }
+}
+
+/// Sleeps until `instant`, calling `on_tick` with the remaining time about once a second.
+pub fn wait_until(
+    clock: &mut impl Clock,
+    instant: DateTime<Utc>,
+    mut on_tick: impl FnMut(Duration),
+) {
+    loop {
+        let remaining = instant - clock.now();
+        if remaining <= Duration::zero() {
+            return;
+        }
+        on_tick(remaining);
+        // sleep up to the next full second of the countdown, so the display stays in step.
+        let step = match remaining.num_milliseconds() % 1000 {
+            0 => 1000,
+            ms => ms,
+        };
+        clock.sleep(StdDuration::from_millis(step as u64));
+    }
+}
+
+/// Delays between attempts, doubling after each failure up to a cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: StdDuration,
+    pub max: StdDuration,
+    pub attempts: u32,
+}
+
+impl Default for Backoff {
+    /// The site is under heavy load right after an unlock; don't add to it.
+    fn default() -> Self {
+        Self {
+            initial: StdDuration::from_secs(5),
+            max: StdDuration::from_secs(60),
+            attempts: 8,
+        }
+    }
+}
+
+impl Backoff {
+    /// Calls `attempt` until it succeeds or the attempts run out, sleeping in between.
+    /// `on_retry` is called with each error and the delay before the next attempt.
+    pub fn retry<T, E>(
+        &self,
+        clock: &mut impl Clock,
+        mut attempt: impl FnMut() -> Result<T, E>,
+        mut on_retry: impl FnMut(&E, StdDuration),
+    ) -> Result<T, E> {
+        let mut delay = self.initial;
+        for _ in 1..self.attempts {
+            match attempt() {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    on_retry(&e, delay);
+                    clock.sleep(delay);
+                    delay = (delay * 2).min(self.max);
+                }
+            }
+        }
+        attempt()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration as StdDuration;
+
+    use chrono::{DateTime, Duration, Utc};
+
+    use super::{format_duration, unlock_time, wait_until, Backoff, Clock};
+    use crate::day;
+
+    /// A clock that advances only when slept on, recording every sleep.
+    struct FakeClock {
+        now: DateTime<Utc>,
+        sleeps: Vec<StdDuration>,
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now
+        }
+
+        fn sleep(&mut self, duration: StdDuration) {
+            self.now += Duration::from_std(duration).unwrap();
+            self.sleeps.push(duration);
+        }
+    }
+
+    fn clock_at(now: DateTime<Utc>) -> FakeClock {
+        FakeClock {
+            now,
+            sleeps: vec![],
+        }
+    }
+
+    #[test]
+    fn unlocks_at_midnight_eastern() {
+        assert_eq!(unlock_time(2023, day!(1)).timestamp(), 1701406800);
+        assert_eq!(
+            unlock_time(2023, day!(25)).to_rfc3339(),
+            "2023-12-25T05:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::seconds(3723)), "01:02:03");
+        assert_eq!(format_duration(Duration::seconds(90000)), "1d 01:00:00");
+        assert_eq!(format_duration(Duration::milliseconds(1)), "00:00:01");
+        assert_eq!(format_duration(Duration::zero()), "00:00:00");
+    }
+
+    #[test]
+    fn waits_until_unlock_with_countdown() {
+        let unlock = unlock_time(2023, day!(5));
+        let mut clock = clock_at(unlock - Duration::milliseconds(2500));
+        let mut ticks = vec![];
+
+        wait_until(&mut clock, unlock, |remaining| {
+            ticks.push(format_duration(remaining))
+        });
+
+        assert_eq!(clock.now, unlock);
+        assert_eq!(ticks, vec!["00:00:03", "00:00:02", "00:00:01"]);
+        assert_eq!(
+            clock.sleeps,
+            vec![
+                StdDuration::from_millis(500),
+                StdDuration::from_secs(1),
+                StdDuration::from_secs(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_wait_after_unlock() {
+        let unlock = unlock_time(2023, day!(5));
+        let mut clock = clock_at(unlock + Duration::seconds(1));
+        wait_until(&mut clock, unlock, |_| panic!("should not tick"));
+        assert!(clock.sleeps.is_empty());
+    }
+
+    #[test]
+    fn retries_with_capped_backoff() {
+        let mut clock = clock_at(unlock_time(2023, day!(1)));
+        let backoff = Backoff {
+            initial: StdDuration::from_secs(5),
+            max: StdDuration::from_secs(15),
+            attempts: 5,
+        };
+
+        let mut calls = 0;
+        let result = backoff.retry(
+            &mut clock,
+            || {
+                calls += 1;
+                if calls < 4 {
+                    Err(calls)
+                } else {
+                    Ok(calls)
+                }
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(result, Ok(4));
+        let secs: Vec<_> = clock.sleeps.iter().map(|d| d.as_secs()).collect();
+        assert_eq!(secs, vec![5, 10, 15]);
+    }
+
+    #[test]
+    fn gives_up_after_all_attempts() {
+        let mut clock = clock_at(unlock_time(2023, day!(1)));
+        let backoff = Backoff {
+            attempts: 3,
+            ..Backoff::default()
+        };
+
+        let mut calls = 0;
+        let result: Result<(), _> = backoff.retry(
+            &mut clock,
+            || {
+                calls += 1;
+                Err("locked")
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(result, Err("locked"));
+        assert_eq!(calls, 3);
+        assert_eq!(clock.sleeps.len(), 2);
+    }
+}
@@ -1,33 +1,50 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+use crate::memo;
+use crate::template::cli::SolutionArgs;
+use crate::template::{ansi, aoc_cli, config, is_quiet, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
+use std::cell::Cell;
 use std::fmt::Display;
 use std::io::{stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, process};
 
-use super::ANSI_BOLD;
-
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+pub fn run_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    args: &SolutionArgs,
+) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    // only the first run counts towards the memo stats, not the benchmark runs.
+    let memo_stats = Cell::new(memo::Stats::default());
+    memo::take_stats();
+    let (result, duration, samples) = run_timed(func, input, args.time, |result| {
+        memo_stats.set(memo::take_stats());
+        print_result(result, &part_str, "")
+    });
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
+    if args.time && !is_quiet() && !memo_stats.get().is_empty() {
+        println!("  memo: {}", memo_stats.get());
+    }
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        submit_result(result, day, part, args.submit);
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched (approx. the configured budget of execution time or the configured
+///     minimum of samples, whatever take longer.)
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    is_timed: bool,
     hook: impl Fn(&T),
 ) -> (T, Duration, u128) {
     let timer = Instant::now();
@@ -36,7 +53,7 @@ fn run_timed<I: Clone, T>(
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let run = if is_timed {
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
@@ -48,16 +65,15 @@ fn run_timed<I: Clone, T>(
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
     let mut stdout = stdout();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if !is_quiet() {
+        print!(" > {}benching{}", ansi(ANSI_ITALIC), ansi(ANSI_RESET));
+        let _ = stdout.flush();
+    }
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_config = &config::get().bench;
+    let bench_iterations = (bench_config.budget().as_nanos() / cmp::max(base_time.as_nanos(), 10))
+        .max(bench_config.min_samples.into())
+        .min(bench_config.max_samples.into());
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -107,7 +123,11 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                     println!("{result}");
                 }
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+                let str = format!(
+                    "{part}: {}{result}{}{duration_str}",
+                    ansi(ANSI_BOLD),
+                    ansi(ANSI_RESET)
+                );
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -127,33 +147,22 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+/// Try to submit one part of the solution if:
+///  1. `--submit` was passed for this part.
+///  2. the configured submit policy allows it.
+///  3. aoc-cli is installed.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
     part: u8,
+    submit_part: Option<u8>,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
-
-    if !args.contains(&"--submit".into()) {
+    if submit_part != Some(part) {
         return None;
     }
 
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    }
-
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
-
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    };
-
-    if part_submit != part {
+    if !config::get().submit.policy.allows_submit() {
+        eprintln!("Submitting is disabled by the configured submit policy.");
         return None;
     }
 
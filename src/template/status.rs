@@ -0,0 +1,273 @@
+/// Progress of every day, gathered from the solution binaries, the data directory and the readme.
+use std::{fmt::Display, fs, path::Path};
+
+use crate::template::render_table;
+use crate::Day;
+
+/// Whether a file exists and has content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    Missing,
+    Empty,
+    Present,
+}
+
+impl FileState {
+    pub fn of(path: impl AsRef<Path>) -> Self {
+        match fs::metadata(path) {
+            Err(_) => FileState::Missing,
+            Ok(m) if m.len() == 0 => FileState::Empty,
+            Ok(_) => FileState::Present,
+        }
+    }
+}
+
+impl Display for FileState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            FileState::Missing => "-",
+            FileState::Empty => "∅",
+            FileState::Present => "✓",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// What a solution printed for one part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartOutput {
+    /// The part returned `None`, or printed nothing.
+    Unsolved,
+    /// A single-line result.
+    Value(String),
+    /// A multi-line result, printed below a `▼`.
+    MultiLine,
+}
+
+/// The outcome of one part of a solution, checked against the answer accepted by the site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartState {
+    /// The solution was not run.
+    Unknown,
+    /// The part returned `None`.
+    Unsolved,
+    /// The part returned a multi-line result, which cannot be compared to an answer.
+    NotComparable,
+    /// The part returned an answer that has not been accepted yet.
+    Solved(String),
+    /// The part returned the accepted answer.
+    Correct(String),
+    /// The part returned something else than the accepted answer.
+    Wrong(String),
+}
+
+impl PartState {
+    /// Checks the output of a part against the accepted answer, if any.
+    pub fn check(output: PartOutput, accepted: Option<&str>) -> Self {
+        match (output, accepted) {
+            (PartOutput::Unsolved, _) => PartState::Unsolved,
+            (PartOutput::MultiLine, _) => PartState::NotComparable,
+            (PartOutput::Value(out), Some(answer)) if out == answer => PartState::Correct(out),
+            (PartOutput::Value(out), Some(_)) => PartState::Wrong(out),
+            (PartOutput::Value(out), None) => PartState::Solved(out),
+        }
+    }
+}
+
+impl Display for PartState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartState::Unknown => write!(f, "-"),
+            PartState::Unsolved => write!(f, "✖"),
+            PartState::NotComparable => write!(f, "~"),
+            PartState::Solved(_) => write!(f, "?"),
+            PartState::Correct(_) => write!(f, "★"),
+            PartState::Wrong(_) => write!(f, "✗"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: Day,
+    pub bin: FileState,
+    pub input: FileState,
+    pub example: FileState,
+    pub puzzle: FileState,
+    pub parts: [PartState; 2],
+    pub benchmarks: [Option<String>; 2],
+}
+
+impl DayStatus {
+    /// Whether the solution can be run against its input.
+    pub fn is_runnable(&self) -> bool {
+        self.bin == FileState::Present && self.input == FileState::Present
+    }
+}
+
+/// Extracts the accepted answers from a puzzle description saved by aoc-cli, in part order.
+pub fn parse_answers(puzzle: &str) -> Vec<String> {
+    const PREFIX: &str = "Your puzzle answer was ";
+
+    puzzle
+        .match_indices(PREFIX)
+        .filter_map(|(pos, _)| {
+            let rest = puzzle[pos + PREFIX.len()..].strip_prefix('`')?;
+            rest.split('`').next().map(str::to_string)
+        })
+        .collect()
+}
+
+/// Extracts the result of each part from the output of a solution binary run without color.
+pub fn parse_outputs(output: &[String]) -> [PartOutput; 2] {
+    let mut results = [PartOutput::Unsolved, PartOutput::Unsolved];
+
+    for line in output {
+        // intermediate results are overwritten with a carriage return.
+        let line = line.rsplit('\r').next().unwrap_or_default();
+        for (part, result) in results.iter_mut().enumerate() {
+            let Some(rest) = line.strip_prefix(&format!("Part {}: ", part + 1)) else {
+                continue;
+            };
+            let value = rest.split(" (").next().unwrap_or_default().trim();
+            // multi-line results are printed below a `▼` and cannot be compared to an answer.
+            if value.starts_with('▼') {
+                *result = PartOutput::MultiLine;
+            } else if !value.is_empty() && value != "✖" {
+                *result = PartOutput::Value(value.to_string());
+            }
+        }
+    }
+
+    results
+}
+
+/// Renders one row per day.
+pub fn render(statuses: &[DayStatus]) -> String {
+    let rows: Vec<Vec<String>> = statuses
+        .iter()
+        .map(|s| {
+            let bench = match &s.benchmarks {
+                [None, None] => "-".to_string(),
+                [p1, p2] => format!(
+                    "{} / {}",
+                    p1.as_deref().unwrap_or("-"),
+                    p2.as_deref().unwrap_or("-")
+                ),
+            };
+            vec![
+                s.day.to_string(),
+                s.bin.to_string(),
+                s.input.to_string(),
+                s.example.to_string(),
+                s.puzzle.to_string(),
+                s.parts[0].to_string(),
+                s.parts[1].to_string(),
+                bench,
+            ]
+        })
+        .collect();
+
+    let header = [
+        "Day",
+        "Bin",
+        "Input",
+        "Example",
+        "Puzzle",
+        "Part 1",
+        "Part 2",
+        "Benchmark",
+    ];
+    let legend = "files: ✓ present, ∅ empty, - missing | parts: ★ correct, ✗ wrong, ? unconfirmed, ~ not comparable, ✖ unsolved, - not run";
+    format!("{}\n\n{legend}", render_table(&header, &rows))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        parse_answers, parse_outputs, render, DayStatus, FileState, PartOutput, PartState,
+    };
+    use crate::day;
+
+    #[test]
+    fn parses_accepted_answers() {
+        let puzzle = "## --- Day 1: Trebuchet?! ---\n...\nYour puzzle answer was `54667`.\n\n\
+                      ## --- Part Two ---\n...\nYour puzzle answer was `54203`.\n\nBoth parts of this puzzle are complete!";
+        assert_eq!(parse_answers(puzzle), vec!["54667", "54203"]);
+        assert!(parse_answers("## --- Day 1 ---\nno answers yet").is_empty());
+    }
+
+    #[test]
+    fn parses_solution_output() {
+        let output = vec!["Part 1: 142 (12.3µs)".to_string(), "Part 2: ✖".to_string()];
+        assert_eq!(
+            parse_outputs(&output),
+            [PartOutput::Value("142".to_string()), PartOutput::Unsolved]
+        );
+
+        let output = vec!["Part 1: ✖\rPart 1: 7 (1.0ms @ 20 samples)".to_string()];
+        assert_eq!(
+            parse_outputs(&output),
+            [PartOutput::Value("7".to_string()), PartOutput::Unsolved]
+        );
+
+        let output = vec![
+            "Part 1: ▼ (1.2ms)".to_string(),
+            "#..#".to_string(),
+            "Part 2: 3".to_string(),
+        ];
+        assert_eq!(
+            parse_outputs(&output),
+            [PartOutput::MultiLine, PartOutput::Value("3".to_string())]
+        );
+    }
+
+    #[test]
+    fn checks_parts_against_answers() {
+        assert_eq!(
+            PartState::check(PartOutput::Unsolved, Some("1")),
+            PartState::Unsolved
+        );
+        assert_eq!(
+            PartState::check(PartOutput::MultiLine, Some("1")),
+            PartState::NotComparable
+        );
+        assert_eq!(
+            PartState::check(PartOutput::Value("1".into()), Some("1")),
+            PartState::Correct("1".into())
+        );
+        assert_eq!(
+            PartState::check(PartOutput::Value("2".into()), Some("1")),
+            PartState::Wrong("2".into())
+        );
+        assert_eq!(
+            PartState::check(PartOutput::Value("2".into()), None),
+            PartState::Solved("2".into())
+        );
+    }
+
+    #[test]
+    fn renders_rows() {
+        let status = DayStatus {
+            day: day!(3),
+            bin: FileState::Present,
+            input: FileState::Empty,
+            example: FileState::Present,
+            puzzle: FileState::Missing,
+            parts: [PartState::Correct("4".into()), PartState::Unknown],
+            benchmarks: [Some("1.2ms".into()), None],
+        };
+        assert!(!status.is_runnable());
+
+        let rendered = render(&[status]);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Day  Bin  Input  Example  Puzzle  Part 1  Part 2  Benchmark"
+        );
+        assert_eq!(
+            lines[1],
+            " 03    ✓      ∅        ✓       -       ★       -  1.2ms / -"
+        );
+    }
+}
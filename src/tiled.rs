@@ -0,0 +1,203 @@
+/// Finite grids repeated infinitely in every direction.
+///
+/// A [`Tiled`] view addresses cells with signed positions, where `(0, 0)` is the top left cell of
+/// the original grid, which is the tile `(0, 0)`. The tile `(1, -2)` is the copy one grid height
+/// below and two grid widths to the left, and so on.
+use std::collections::{HashMap, VecDeque};
+
+use crate::direction::Direction;
+use crate::grid::{Grid, Pos};
+
+/// A position in the infinite grid, as a signed `(row, column)`.
+pub type SignedPos = (isize, isize);
+
+/// Which copy of the grid a position is in, as a signed `(row, column)` of copies.
+pub type Tile = (isize, isize);
+
+/// A view of a grid as an infinite periodic one.
+#[derive(Debug, Clone, Copy)]
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> Tiled<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Tiled { grid }
+    }
+
+    /// The `(height, width)` of a single tile.
+    pub fn tile_size(&self) -> (usize, usize) {
+        self.grid.size()
+    }
+
+    /// The tile of a position and the position within it.
+    pub fn split(&self, (i, j): SignedPos) -> (Tile, Pos) {
+        let (h, w) = (self.grid.height() as isize, self.grid.width() as isize);
+        (
+            (i.div_euclid(h), j.div_euclid(w)),
+            (i.rem_euclid(h) as usize, j.rem_euclid(w) as usize),
+        )
+    }
+
+    /// The tile that a position is in.
+    pub fn tile_of(&self, pos: SignedPos) -> Tile {
+        self.split(pos).0
+    }
+
+    /// The position in the original grid of the cell that `pos` is a copy of.
+    pub fn wrap(&self, pos: SignedPos) -> Pos {
+        self.split(pos).1
+    }
+
+    /// The position of `pos` of the original grid in the copy at `tile`.
+    pub fn unwrap(&self, tile: Tile, (i, j): Pos) -> SignedPos {
+        let (h, w) = (self.grid.height() as isize, self.grid.width() as isize);
+        (tile.0 * h + i as isize, tile.1 * w + j as isize)
+    }
+
+    pub fn get(&self, pos: SignedPos) -> &'a T {
+        &self.grid[self.wrap(pos)]
+    }
+
+    /// The number of moves to every position within `max_distance` moves of `start`, moving
+    /// orthogonally between positions for which `passable` holds. The start must be passable.
+    pub fn bfs_distances(
+        &self,
+        start: SignedPos,
+        max_distance: usize,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> TiledDistances {
+        let mut distances = TiledDistances {
+            tile_size: self.tile_size(),
+            tiles: HashMap::new(),
+        };
+        if !passable(self.get(start)) {
+            return distances;
+        }
+
+        distances.insert(self.split(start), 0);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((pos, d)) = queue.pop_front() {
+            if d == max_distance {
+                continue;
+            }
+            for dir in Direction::CARDINAL {
+                let next = dir.advance(pos, 1);
+                let (tile, local) = self.split(next);
+                if passable(&self.grid[local]) && distances.insert((tile, local), d + 1) {
+                    queue.push_back((next, d + 1));
+                }
+            }
+        }
+        distances
+    }
+}
+
+/// The distances found by [`Tiled::bfs_distances`], stored per tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledDistances {
+    tile_size: (usize, usize),
+    tiles: HashMap<Tile, Grid<Option<usize>>>,
+}
+
+impl TiledDistances {
+    /// Records a distance unless one is known already, returning whether it was new.
+    fn insert(&mut self, (tile, pos): (Tile, Pos), d: usize) -> bool {
+        let cells = self
+            .tiles
+            .entry(tile)
+            .or_insert_with(|| Grid::from_elem(self.tile_size, None));
+        let cell = &mut cells[pos];
+        if cell.is_some() {
+            return false;
+        }
+        *cell = Some(d);
+        true
+    }
+
+    /// The distances in a tile, if any of its cells was reached.
+    pub fn tile(&self, tile: Tile) -> Option<&Grid<Option<usize>>> {
+        self.tiles.get(&tile)
+    }
+
+    /// The tiles that were reached, in no particular order.
+    pub fn tiles(&self) -> impl Iterator<Item = (Tile, &Grid<Option<usize>>)> + '_ {
+        self.tiles.iter().map(|(&tile, cells)| (tile, cells))
+    }
+
+    /// Every reached position with its distance, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((Tile, Pos), usize)> + '_ {
+        self.tiles().flat_map(|(tile, cells)| {
+            cells
+                .indexed_iter()
+                .filter_map(move |(pos, d)| d.map(|d| ((tile, pos), d)))
+        })
+    }
+
+    /// The number of reached positions in every tile whose distance satisfies `predicate`, e.g. the
+    /// ones reachable in exactly some number of moves.
+    pub fn count_by_tile(&self, mut predicate: impl FnMut(usize) -> bool) -> HashMap<Tile, usize> {
+        self.tiles()
+            .map(|(tile, cells)| {
+                let count = cells.iter().flatten().filter(|&&d| predicate(d)).count();
+                (tile, count)
+            })
+            .collect()
+    }
+
+    /// The number of reached positions whose distance satisfies `predicate`.
+    pub fn count(&self, predicate: impl FnMut(usize) -> bool) -> usize {
+        self.count_by_tile(predicate).values().sum()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Tiled;
+    use crate::grid::Grid;
+
+    fn walls(input: &str) -> Grid<bool> {
+        Grid::parse(input, |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn wraps_positions() {
+        let grid = walls("..#\n...");
+        let tiled = Tiled::new(&grid);
+        assert_eq!(tiled.split((0, 2)), ((0, 0), (0, 2)));
+        assert_eq!(tiled.split((-1, -1)), ((-1, -1), (1, 2)));
+        assert_eq!(tiled.split((5, -4)), ((2, -2), (1, 2)));
+        assert_eq!(tiled.unwrap((2, -2), (1, 2)), (5, -4));
+        assert!(*tiled.get((2, -1)));
+        assert_eq!(tiled.tile_of((-3, 3)), (-2, 1));
+    }
+
+    #[test]
+    fn measures_distances_across_tiles() {
+        // a wall that has to be walked around in every tile.
+        let grid = walls("...\n.#.\n...");
+        let tiled = Tiled::new(&grid);
+        let distances = tiled.bfs_distances((1, 0), 4, |&wall| !wall);
+
+        let d = |pos| {
+            let (tile, local) = tiled.split(pos);
+            distances.tile(tile).and_then(|cells| cells[local])
+        };
+        assert_eq!(d((1, 0)), Some(0));
+        assert_eq!(d((1, 2)), Some(4));
+        assert_eq!(d((1, -1)), Some(1));
+        assert_eq!(d((1, -2)), None);
+        assert_eq!(d((0, -2)), Some(3));
+        assert_eq!(d((1, -3)), None);
+
+        // the cells within 4 moves, minus the walls of the tiles in reach.
+        let reached = distances.count(|_| true);
+        assert_eq!(reached, distances.iter().count());
+        let by_tile = distances.count_by_tile(|d| d % 2 == 0);
+        assert_eq!(
+            by_tile.values().sum::<usize>(),
+            distances.count(|d| d % 2 == 0)
+        );
+        assert_eq!(by_tile[&(0, 0)], 4);
+    }
+}